@@ -0,0 +1,369 @@
+use crate::{
+    components::*,
+    config::{load_config, save_config},
+};
+use bevy::{asset::LoadState, prelude::*};
+
+// hit sounds, bounces and the crowd
+pub(crate) struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_config::<AudioSettings>("ld51_audio").unwrap_or_default())
+            .insert_resource(AudioPool::default())
+            .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(resume_ambience))
+            .add_system_set(SystemSet::on_enter(AppState::GameOver).with_system(pause_ambience))
+            .add_system(play_hit_sound)
+            .add_system(play_hazard_warning)
+            .add_system(play_bounce_sound)
+            .add_system(expire_voices)
+            .add_system(update_ambience)
+            .add_system(crossfade_music)
+            .add_system(update_music.after(crossfade_music))
+            .add_system(toggle_mute);
+    }
+}
+
+// every power hit, whichever way the hit stop is done
+fn play_hit_sound(
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    mut audio_pool: ResMut<AudioPool>,
+    hit_sound: Res<HitSound>,
+    mut hit_events: EventReader<HitEvent>,
+) {
+    for event in hit_events.iter() {
+        if !matches!(
+            event,
+            HitEvent::Ball {
+                power_hit: true,
+                ..
+            }
+        ) {
+            continue;
+        }
+
+        play_pooled(
+            &mut audio_pool,
+            &audio,
+            &audio_sinks,
+            &audio_settings,
+            PooledSound {
+                source: hit_sound.0.clone_weak(),
+                category: SoundCategory::Hit,
+                important: true,
+                volume: 1.0,
+                speed: 1.0,
+            },
+        );
+    }
+}
+
+// higher, quieter tick whenever a hit ball bounces
+fn play_bounce_sound(
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    mut audio_pool: ResMut<AudioPool>,
+    hit_sound: Res<HitSound>,
+    q: Query<&BounceCount, Changed<BounceCount>>,
+) {
+    for bounce_count in q.iter() {
+        if bounce_count.0 == 0 {
+            continue;
+        }
+
+        play_pooled(
+            &mut audio_pool,
+            &audio,
+            &audio_sinks,
+            &audio_settings,
+            PooledSound {
+                source: hit_sound.0.clone_weak(),
+                category: SoundCategory::Bounce,
+                important: false,
+                volume: 0.3,
+                speed: 1.8,
+            },
+        );
+    }
+}
+
+// plays a one shot if its category has a free voice
+// important sounds steal the oldest voice instead, preferring unimportant ones
+fn play_pooled(
+    audio_pool: &mut AudioPool,
+    audio: &Audio,
+    audio_sinks: &Assets<AudioSink>,
+    audio_settings: &AudioSettings,
+    sound: PooledSound,
+) {
+    let playing = audio_pool
+        .0
+        .iter()
+        .filter(|voice| voice.category == sound.category)
+        .count();
+
+    if playing >= audio_settings.max_voices(sound.category) {
+        if !sound.important {
+            return;
+        }
+
+        let stolen = audio_pool
+            .0
+            .iter()
+            .position(|voice| voice.category == sound.category && !voice.important)
+            .or_else(|| {
+                audio_pool
+                    .0
+                    .iter()
+                    .position(|voice| voice.category == sound.category)
+            });
+        if let Some(index) = stolen {
+            let voice = audio_pool.0.remove(index);
+            if let Some(sink) = audio_sinks.get(&voice.sink) {
+                sink.pause();
+            }
+        }
+    }
+
+    let sink = audio.play_with_settings(
+        sound.source,
+        PlaybackSettings {
+            repeat: false,
+            volume: sound.volume * audio_settings.volume(),
+            speed: sound.speed,
+        },
+    );
+    audio_pool.0.push(Voice {
+        category: sound.category,
+        sink: audio_sinks.get_handle(sink),
+        important: sound.important,
+        remaining: VOICE_TIME / sound.speed,
+    });
+}
+
+fn expire_voices(time: Res<Time>, mut audio_pool: ResMut<AudioPool>) {
+    for voice in audio_pool.0.iter_mut() {
+        voice.remaining -= time.delta_seconds();
+    }
+    audio_pool.0.retain(|voice| voice.remaining > 0.0);
+}
+
+fn toggle_mute(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut audio_settings: ResMut<AudioSettings>,
+) {
+    if keys.just_pressed(key_bindings.toggle_mute) {
+        audio_settings.muted = !audio_settings.muted;
+        save_config("ld51_audio", &*audio_settings);
+    }
+}
+
+// starts the loop the first time, picks it back up after a game over
+fn resume_ambience(
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    mut ambience: ResMut<AmbienceController>,
+) {
+    let source = match &ambience.source {
+        Some(source) => source.clone(),
+        None => return,
+    };
+
+    match ambience
+        .sink
+        .as_ref()
+        .and_then(|sink| audio_sinks.get(sink))
+    {
+        Some(sink) => sink.play(),
+        None => {
+            let sink = audio.play_with_settings(
+                source,
+                PlaybackSettings {
+                    repeat: true,
+                    volume: AMBIENCE_VOLUME * audio_settings.volume(),
+                    ..default()
+                },
+            );
+            ambience.sink = Some(audio_sinks.get_handle(sink));
+        }
+    }
+}
+
+fn pause_ambience(audio_sinks: Res<Assets<AudioSink>>, ambience: Res<AmbienceController>) {
+    if let Some(sink) = ambience
+        .sink
+        .as_ref()
+        .and_then(|sink| audio_sinks.get(sink))
+    {
+        sink.pause();
+    }
+}
+
+fn update_ambience(
+    time: Res<Time>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    mut ambience: ResMut<AmbienceController>,
+    mut hit_events: EventReader<HitEvent>,
+    q_home_runs: Query<(), Added<HomeRun>>,
+) {
+    // the crowd roars on power hits and home runs
+    for event in hit_events.iter() {
+        if let HitEvent::Ball {
+            power_hit: true, ..
+        } = event
+        {
+            ambience.swell = 1.0;
+        }
+    }
+    if !q_home_runs.is_empty() {
+        ambience.swell = 1.0;
+    }
+
+    ambience.swell = (ambience.swell - time.delta_seconds() * AMBIENCE_FADE).max(0.0);
+
+    if let Some(sink) = ambience
+        .sink
+        .as_ref()
+        .and_then(|sink| audio_sinks.get(sink))
+    {
+        sink.set_volume(
+            (AMBIENCE_VOLUME + ambience.swell * AMBIENCE_ROAR) * audio_settings.volume(),
+        );
+    }
+}
+
+// stems fade toward full once the combo reaches them, and out again when it breaks
+fn update_music(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    app_state: Res<State<AppState>>,
+    combo: Res<Combo>,
+    music_controller: Res<MusicController>,
+    mut music: ResMut<MusicIntensity>,
+) {
+    // wait for every stem so they all start on the same frame
+    if !music.started {
+        let loaded = music
+            .stems
+            .iter()
+            .all(|stem| asset_server.get_load_state(&stem.source) == LoadState::Loaded);
+        if !loaded || *app_state.current() == AppState::GameOver {
+            return;
+        }
+
+        for stem in music.stems.iter_mut() {
+            let sink = audio.play_with_settings(
+                stem.source.clone(),
+                PlaybackSettings {
+                    repeat: true,
+                    volume: 0.0,
+                    ..default()
+                },
+            );
+            stem.sink = Some(audio_sinks.get_handle(sink));
+        }
+        music.started = true;
+    }
+
+    let step = time.delta_seconds() * MUSIC_STEM_FADE;
+    for stem in music.stems.iter_mut() {
+        let target = if combo.count >= stem.combo { 1.0 } else { 0.0 };
+        stem.volume = fade_toward(stem.volume, target, step);
+
+        if let Some(sink) = stem.sink.as_ref().and_then(|sink| audio_sinks.get(sink)) {
+            sink.set_volume(
+                stem.volume * music_controller.game_level * MUSIC_VOLUME * audio_settings.volume(),
+            );
+        }
+    }
+}
+
+// the stems play in game, the menu loop in breaks and menus, and both fade out on game over
+// both keep running underneath so they come back in where they left off
+fn crossfade_music(
+    time: Res<Time>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    app_state: Res<State<AppState>>,
+    mut music: ResMut<MusicController>,
+) {
+    let (menu_target, game_target) = match app_state.current() {
+        AppState::InGame | AppState::HitPause => (0.0, 1.0),
+        AppState::InningBreak | AppState::PhotoMode | AppState::Settings => (1.0, 0.0),
+        AppState::GameOver => (0.0, 0.0),
+    };
+    let step = time.delta_seconds() * MUSIC_CROSSFADE;
+    music.menu_level = fade_toward(music.menu_level, menu_target, step);
+    music.game_level = fade_toward(music.game_level, game_target, step);
+
+    if music.menu_sink.is_none() {
+        let loaded = music
+            .menu
+            .clone()
+            .filter(|menu| asset_server.get_load_state(menu) == LoadState::Loaded);
+        if let Some(menu) = loaded {
+            let sink = audio.play_with_settings(
+                menu,
+                PlaybackSettings {
+                    repeat: true,
+                    volume: 0.0,
+                    ..default()
+                },
+            );
+            music.menu_sink = Some(audio_sinks.get_handle(sink));
+        }
+    }
+
+    if let Some(sink) = music
+        .menu_sink
+        .as_ref()
+        .and_then(|sink| audio_sinks.get(sink))
+    {
+        sink.set_volume(music.menu_level * MUSIC_VOLUME * audio_settings.volume());
+    }
+}
+
+fn fade_toward(level: f32, target: f32, step: f32) -> f32 {
+    if level < target {
+        (level + step).min(target)
+    } else {
+        (level - step).max(target)
+    }
+}
+
+// low thud warning for bombs
+fn play_hazard_warning(
+    audio: Res<Audio>,
+    audio_sinks: Res<Assets<AudioSink>>,
+    audio_settings: Res<AudioSettings>,
+    mut audio_pool: ResMut<AudioPool>,
+    hit_sound: Res<HitSound>,
+    q: Query<(), Added<Hazard>>,
+) {
+    for _ in q.iter() {
+        play_pooled(
+            &mut audio_pool,
+            &audio,
+            &audio_sinks,
+            &audio_settings,
+            PooledSound {
+                source: hit_sound.0.clone_weak(),
+                category: SoundCategory::Ui,
+                important: true,
+                volume: 0.5,
+                speed: 0.5,
+            },
+        );
+    }
+}
@@ -0,0 +1,2260 @@
+use bevy::{
+    ecs::system::SystemParam,
+    math::vec3,
+    prelude::*,
+    utils::{HashMap, Instant},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, marker::PhantomData};
+
+// defines
+pub(crate) static PAUSE_TIME: f32 = 0.7;
+pub(crate) static PITCH_TIME: f64 = 1.0;
+// the next pitch's origin lights up this long before it's thrown
+pub(crate) static SPAWN_WARNING_TIME: f32 = 0.4;
+// pitch speeds the warning colors map from green to red, curveballs read a bit nastier
+pub(crate) static EASY_PITCH_SPEED: f32 = 5.0;
+pub(crate) static HARD_PITCH_SPEED: f32 = 12.0;
+pub(crate) static CURVEBALL_DIFFICULTY: f32 = 0.25;
+// dots along a curveball's predicted path, covering at most this many seconds ahead
+pub(crate) static CURVE_ARC_DOTS: usize = 12;
+pub(crate) static CURVE_ARC_TIME: f32 = 0.8;
+// dots tracing the bat tip across a full swing at the current aim height
+pub(crate) static SWING_ARC_DOTS: usize = 16;
+// balls grow in when thrown and shrink away when caught
+pub(crate) static BALL_SPAWN_TIME: f32 = 0.15;
+pub(crate) static BALL_DESPAWN_TIME: f32 = 0.2;
+// how long a struck ball hangs before flying off in local hit-stop
+pub(crate) static HIT_STOP_TIME: f32 = 0.15;
+pub(crate) static GRAVITY: f32 = 2.0;
+// sideways acceleration of a curveball
+pub(crate) static CURVE_BREAK: f32 = 1.5;
+pub(crate) static HAZARD_CHANCE: f32 = 0.1;
+// pressure balls gain this fraction of their speed per second until hit, and cost extra outs
+pub(crate) static PRESSURE_BALL_CHANCE: f32 = 0.08;
+pub(crate) static PRESSURE_BALL_RATE: f32 = 0.6;
+pub(crate) static PRESSURE_BALL_OUTS: u32 = 2;
+pub(crate) static PRESSURE_PULSE_RATE: f32 = 12.0;
+// chance a pitch is thrown as a balloon or a stone instead of a regular ball
+pub(crate) static BALLOON_CHANCE: f32 = 0.08;
+pub(crate) static STONE_CHANCE: f32 = 0.08;
+pub(crate) static SANDBOX_REACH: f32 = 6.0;
+// wild pitches cross the plate this far to the side of the strike point
+pub(crate) static WILD_PITCH_MISS: f32 = 1.2;
+// and the spawn warning wobbles this much before one
+pub(crate) static WILD_PITCH_WOBBLE: f32 = 0.15;
+pub(crate) static HAZARD_PASS_SCORE: u32 = 5;
+pub(crate) static BASE_BALL_SIZE: f32 = 0.05;
+pub(crate) static BASE_HIT_SCORE: f32 = 10.0;
+// balls past this x + z have gone by the player
+pub(crate) static MISS_LINE: f32 = 10.0;
+// hit balls further than this from the origin have cleared the walls
+pub(crate) static FENCE_DISTANCE: f32 = 5.0;
+pub(crate) static CATCH_DISTANCE: f32 = 0.3;
+pub(crate) static CATCH_HEIGHT: f32 = 1.0;
+// fly balls hanging longer than this can be chased down for a bonus before the fielders get there
+pub(crate) static FIELDING_MIN_HANG: f32 = 1.2;
+pub(crate) static FIELDING_BONUS: u32 = 25;
+pub(crate) static PLAYER_FIELDER_SPEED: f32 = 2.5;
+// the aim maps onto a square of this half size around the home spot
+pub(crate) static PLAYER_FIELDER_HOME: Vec3 = Vec3::new(-1.5, 0.4, -1.5);
+pub(crate) static PLAYER_FIELDER_REACH: f32 = 3.0;
+pub(crate) static BAT_VISUAL_OFFSET: f32 = 0.8;
+pub(crate) static BAT_SHRINK_RATE: f32 = 0.005;
+pub(crate) static MIN_BAT_SCALE: f32 = 0.5;
+// floppy bat springs step at most this long, and drop any frame time past the max
+pub(crate) static SPRING_STEP: f32 = 1.0 / 120.0;
+pub(crate) static MAX_SPRING_DT: f32 = 0.1;
+// the co-op bat stands this far to the right of the first one
+pub(crate) static CO_OP_BAT_OFFSET: f32 = 0.6;
+// extra sideways push from the swing's path across the ball
+pub(crate) static SWING_PULL: f32 = 10.0;
+// trauma added per unit of hit power, and lost per second
+pub(crate) static TRAUMA_PER_POWER: f32 = 1.5;
+pub(crate) static HAZARD_TRAUMA: f32 = 0.6;
+pub(crate) static TRAUMA_DECAY: f32 = 1.2;
+// full trauma shakes this far and turns this many radians
+pub(crate) static MAX_SHAKE_OFFSET: f32 = 0.3;
+pub(crate) static MAX_SHAKE_ANGLE: f32 = 0.05;
+pub(crate) static SHAKE_FREQUENCY: f32 = 15.0;
+// camera presets as an offset from the player and a downward tilt, eased at this rate
+pub(crate) static CAMERA_PRESETS: [(&str, Vec3, f32); 3] = [
+    ("close", Vec3::new(0.0, -0.1, -1.0), 0.0),
+    ("medium", Vec3::new(0.0, 0.0, 0.0), 0.0),
+    ("far", Vec3::new(0.0, 1.0, 2.0), -0.25),
+];
+pub(crate) static CAMERA_PRESET_EASE: f32 = 4.0;
+// fov zoom in radians per unit of hit power
+pub(crate) static FOV_PUNCH: f32 = 0.15;
+pub(crate) static MAX_FOV_PUNCH: f32 = 0.2;
+// cinematic camera after power hits
+pub(crate) static FOLLOW_TIME: f32 = 2.0;
+pub(crate) static FOLLOW_BLEND_TIME: f32 = 0.3;
+pub(crate) static FOLLOW_DOLLY: f32 = 1.5;
+// ghost copies left along a power hit's launch, this many seconds of flight apart
+pub(crate) static AFTERIMAGE_COUNT: usize = 4;
+pub(crate) static AFTERIMAGE_STEP: f32 = 0.03;
+pub(crate) static AFTERIMAGE_TIME: f32 = 0.25;
+pub(crate) static AFTERIMAGE_ALPHA: f32 = 0.5;
+// slow motion chase of home runs
+pub(crate) static BALL_CAM_TIME: f32 = 2.0;
+pub(crate) static BALL_CAM_TIME_SCALE: f32 = 0.3;
+pub(crate) static BALL_CAM_DISTANCE: f32 = 1.2;
+// ball cam cuts back once the ball is this far out
+pub(crate) static BALL_CAM_MAX_DISTANCE: f32 = 12.0;
+// free camera speed in photo mode, units per second and radians per pixel dragged
+pub(crate) static PHOTO_MOVE_SPEED: f32 = 3.0;
+pub(crate) static PHOTO_LOOK_SPEED: f32 = 0.005;
+pub(crate) static TARGET_RADIUS: f32 = 0.5;
+// finishing a target sequence faster than this earns a bonus
+pub(crate) static TARGET_PAR_TIME: f32 = 20.0;
+pub(crate) static MIN_BAT_COLLIDERS: u32 = 3;
+pub(crate) static MAX_BAT_COLLIDERS: u32 = 15;
+pub(crate) static FRAME_GRAPH_SAMPLES: usize = 60;
+// pixels per millisecond, bars are capped at FRAME_GRAPH_HEIGHT
+pub(crate) static FRAME_GRAPH_SCALE: f32 = 2.0;
+pub(crate) static FRAME_GRAPH_HEIGHT: f32 = 66.0;
+// frame rate caps cycled through in order, None runs uncapped
+pub(crate) static FRAME_CAPS: [Option<f64>; 4] = [None, Some(30.0), Some(60.0), Some(120.0)];
+// fractions of the window resolution the world is rendered at, cycled through in order
+pub(crate) static RENDER_SCALES: [f32; 4] = [1.0, 0.75, 0.5, 0.35];
+// the swing meter spans twice the power hit threshold, so the threshold sits in the middle
+pub(crate) static SWING_METER_WIDTH: f32 = 150.0;
+// swing power per second the peak marker falls back
+pub(crate) static SWING_PEAK_DECAY: f32 = 0.5;
+// seconds to land the next power hit before the combo breaks, shown as a shrinking bar
+pub(crate) static COMBO_WINDOW: f32 = 5.0;
+pub(crate) static COMBO_BAR_WIDTH: f32 = 100.0;
+// bat glow color at full power hit speed
+pub(crate) static SWING_GLOW_COLOR: Color = Color::rgb(1.0, 0.35, 0.05);
+// ground grid texture, one cell per meter of the 10m plane, lines this many pixels wide
+pub(crate) static GROUND_GRID_RESOLUTION: usize = 1024;
+pub(crate) static GROUND_GRID_CELLS: usize = 10;
+pub(crate) static GROUND_GRID_LINE_WIDTH: f32 = 3.0;
+// hard hit balls flash this color and cool back down over HOT_BALL_TIME seconds
+pub(crate) static HOT_BALL_COLOR: Color = Color::rgb(1.0, 0.55, 0.1);
+pub(crate) static HOT_BALL_TIME: f32 = 1.5;
+// non power hits above this fraction of the threshold were close
+pub(crate) static NEAR_POWER_HIT: f32 = 0.8;
+pub(crate) static HEATMAP_ROWS: usize = 12;
+pub(crate) static HEATMAP_COLS: usize = 5;
+pub(crate) static AMBIENCE_VOLUME: f32 = 0.2;
+pub(crate) static AMBIENCE_ROAR: f32 = 0.6;
+pub(crate) static AMBIENCE_FADE: f32 = 0.5;
+pub(crate) static MUSIC_VOLUME: f32 = 0.3;
+// stem volume change per second while fading in or out
+pub(crate) static MUSIC_STEM_FADE: f32 = 0.5;
+// level change per second when crossfading between the menu loop and the stems
+pub(crate) static MUSIC_CROSSFADE: f32 = 1.0;
+// music stems and the combo each one comes in at, the first always plays
+pub(crate) static MUSIC_STEMS: [(&str, u32); 3] = [
+    ("music_base.ogg", 0),
+    ("music_drums.ogg", 2),
+    ("music_lead.ogg", 4),
+];
+// how long a one shot holds its voice
+pub(crate) static VOICE_TIME: f32 = 0.6;
+pub(crate) static AI_SWING_LEAD: f32 = 0.12;
+pub(crate) static AI_BACKSWING: f32 = 0.35;
+pub(crate) static AI_MISTIME_CHANCE: f32 = 0.2;
+// aim assist widens the bat by up to this much for a brand new player
+pub(crate) static MAX_AIM_ASSIST: f32 = 0.5;
+pub(crate) static AIM_ASSIST_RUNS: u32 = 5;
+pub(crate) static AIM_ASSIST_WINDOW: usize = 10;
+pub(crate) static RUN_HISTORY_LENGTH: usize = 10;
+// recorded frame times are whole multiples of this many microseconds
+pub(crate) static SHARE_DT_MICROS: u64 = 100;
+pub(crate) static SHARE_CODE_VERSION: u8 = 2;
+pub(crate) static PITCHES_PER_INNING: u32 = 10;
+// aim moved per unit of mouse motion with relative aim, the full range is 1.0
+pub(crate) static RELATIVE_AIM_RATE: f32 = 0.002;
+// match lengths to pick from, None plays until struck out
+pub(crate) static MATCH_LENGTHS: [Option<u32>; 3] = [None, Some(9), Some(18)];
+pub(crate) static INNING_BREAK_TIME: f32 = 3.0;
+// pitches get this much faster every inning
+pub(crate) static INNING_SPEEDUP: f32 = 0.08;
+pub(crate) static RICOCHET_SCORE: u32 = 10;
+pub(crate) static RICOCHET_MIN_SPEED: f32 = 0.5;
+// balls this close to resting height count as touching the ground
+pub(crate) static GROUND_CONTACT_MARGIN: f32 = 0.001;
+// game units per second to radar gun mph
+pub(crate) static RADAR_MPH: f32 = 12.0;
+pub(crate) static RADAR_TIME: f32 = 2.0;
+pub(crate) static SCOREBOARD_DIGITS: usize = 5;
+// lit segments a-g of a seven segment display, bit 0 is a
+pub(crate) static SEVEN_SEGMENT_DIGITS: [u8; 10] = [
+    0b0111111, 0b0000110, 0b1011011, 0b1001111, 0b1100110, 0b1101101, 0b1111101, 0b0000111,
+    0b1111111, 0b1101111,
+];
+pub(crate) static WIND_SHIFT_TIME: f64 = 10.0;
+pub(crate) static MAX_WIND: f32 = 1.0;
+#[cfg(feature = "debug")]
+pub(crate) static STEP_DT: f32 = 1.0 / 60.0;
+// debug spawns snap to this grid on the ground
+#[cfg(feature = "debug")]
+pub(crate) static DEBUG_SPAWN_GRID: f32 = 0.25;
+// seconds a debug spawn takes to reach the strike point, cycled with a key
+#[cfg(feature = "debug")]
+pub(crate) static DEBUG_SPAWN_FLIGHT_TIMES: [f32; 4] = [0.5, 0.8, 1.2, 2.0];
+// each press of the debug time keys scales the simulation by this, within the min and max
+#[cfg(feature = "debug")]
+pub(crate) static DEBUG_TIME_SCALE_STEP: f32 = 1.25;
+#[cfg(feature = "debug")]
+pub(crate) static DEBUG_TIME_SCALE_MIN: f32 = 0.1;
+#[cfg(feature = "debug")]
+pub(crate) static DEBUG_TIME_SCALE_MAX: f32 = 4.0;
+// collider tuning keys change the hit margin, or the collider radius with shift held, this much
+#[cfg(feature = "debug")]
+pub(crate) static DEBUG_COLLIDER_STEP: f32 = 0.01;
+
+// resources
+pub(crate) struct HitSound(pub(crate) Handle<AudioSource>);
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct AudioSettings {
+    pub(crate) master_volume: f32,
+    pub(crate) muted: bool,
+    // one shots allowed to play at once per category
+    pub(crate) max_hit_voices: usize,
+    pub(crate) max_bounce_voices: usize,
+    pub(crate) max_ui_voices: usize,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            muted: false,
+            max_hit_voices: 4,
+            max_bounce_voices: 3,
+            max_ui_voices: 2,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub(crate) fn volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume
+        }
+    }
+
+    pub(crate) fn max_voices(&self, category: SoundCategory) -> usize {
+        match category {
+            SoundCategory::Hit => self.max_hit_voices,
+            SoundCategory::Bounce => self.max_bounce_voices,
+            SoundCategory::Ui => self.max_ui_voices,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum SoundCategory {
+    Hit,
+    Bounce,
+    Ui,
+}
+
+pub(crate) struct PooledSound {
+    pub(crate) source: Handle<AudioSource>,
+    pub(crate) category: SoundCategory,
+    // important sounds take over a voice instead of being dropped
+    pub(crate) important: bool,
+    pub(crate) volume: f32,
+    pub(crate) speed: f32,
+}
+
+pub(crate) struct Voice {
+    pub(crate) category: SoundCategory,
+    pub(crate) sink: Handle<AudioSink>,
+    pub(crate) important: bool,
+    pub(crate) remaining: f32,
+}
+
+// one shots still ringing out, so each category can be capped
+#[derive(Default)]
+pub(crate) struct AudioPool(pub(crate) Vec<Voice>);
+
+// one looping layer of the music
+pub(crate) struct MusicStem {
+    pub(crate) source: Handle<AudioSource>,
+    pub(crate) sink: Option<Handle<AudioSink>>,
+    pub(crate) combo: u32,
+    pub(crate) volume: f32,
+}
+
+// music layers, all started together once loaded so they loop in sync
+pub(crate) struct MusicIntensity {
+    pub(crate) stems: Vec<MusicStem>,
+    pub(crate) started: bool,
+}
+
+// calm loop for breaks and menus, crossfaded against the stems, levels go from 0 to 1
+pub(crate) struct MusicController {
+    pub(crate) menu: Option<Handle<AudioSource>>,
+    pub(crate) menu_sink: Option<Handle<AudioSink>>,
+    pub(crate) menu_level: f32,
+    pub(crate) game_level: f32,
+}
+
+// crowd loop, swells on power hits and home runs
+pub(crate) struct AmbienceController {
+    pub(crate) source: Option<Handle<AudioSource>>,
+    pub(crate) sink: Option<Handle<AudioSink>>,
+    pub(crate) swell: f32,
+}
+
+pub(crate) struct LastMousePosition(pub(crate) Vec2);
+
+// practice aid, one pitch held in mid air with its velocity set aside until released
+#[derive(Default)]
+pub(crate) struct StudyFreeze {
+    pub(crate) ball: Option<Entity>,
+    pub(crate) velocity: Vec3,
+}
+
+// relative aim moves the aim by mouse motion instead of following the cursor, the same
+// distance at any window size, sensitivity scales RELATIVE_AIM_RATE
+#[derive(Serialize, Deserialize)]
+pub(crate) struct MouseAim {
+    pub(crate) relative: bool,
+    pub(crate) sensitivity: f32,
+}
+
+impl Default for MouseAim {
+    fn default() -> Self {
+        Self {
+            relative: false,
+            sensitivity: 1.0,
+        }
+    }
+}
+
+// how the window holds on to the cursor, so a wild swing can't click out of the game
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub(crate) enum CursorMode {
+    Free,
+    Confined,
+    // confined and hidden, only the bat shows where it points
+    Hidden,
+}
+
+impl CursorMode {
+    pub(crate) const ALL: [CursorMode; 3] =
+        [CursorMode::Free, CursorMode::Confined, CursorMode::Hidden];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            CursorMode::Free => "free",
+            CursorMode::Confined => "confined",
+            CursorMode::Hidden => "hidden",
+        }
+    }
+
+    pub(crate) fn grabbed(&self) -> bool {
+        *self != CursorMode::Free
+    }
+}
+
+// where the bat is pointed, -0.5..0.5 across and up the screen
+#[derive(Default)]
+pub(crate) struct BatAim(pub(crate) Vec2);
+
+// the co-op bat's aim from the left stick, same range as BatAim
+#[derive(Default)]
+pub(crate) struct GamepadAim(pub(crate) Vec2);
+
+// a second bat on the gamepad, sharing the pitches and the score
+pub(crate) struct CoOp(pub(crate) bool);
+
+// demo batter, drives BatAim instead of the mouse when enabled
+#[derive(Default)]
+pub(crate) struct AiDriver {
+    pub(crate) enabled: bool,
+    pub(crate) ball: Option<Entity>,
+    // seconds before contact to start the swing, rerolled per pitch
+    pub(crate) swing_lead: f32,
+}
+
+// pitch types throw_ball picks from, by weight
+pub(crate) struct PitchDeck {
+    pub(crate) name: &'static str,
+    pub(crate) pitches: Vec<(PitchType, u32)>,
+}
+
+impl PitchDeck {
+    pub(crate) fn presets() -> Vec<PitchDeck> {
+        let deck = |name, fastball, curveball, changeup| PitchDeck {
+            name,
+            pitches: vec![
+                (PitchType::Fastball, fastball),
+                (PitchType::Curveball, curveball),
+                (PitchType::Changeup, changeup),
+            ],
+        };
+        vec![
+            deck("Standard", 1, 1, 1),
+            deck("Heat", 1, 0, 0),
+            deck("Breaking Balls Only", 0, 1, 0),
+            deck("Off-Speed", 0, 1, 2),
+        ]
+    }
+
+    pub(crate) fn sample(&self, rng: &mut StdRng) -> PitchType {
+        let total: u32 = self.pitches.iter().map(|(_, weight)| weight).sum();
+        if total == 0 {
+            return PitchType::Fastball;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for (pitch_type, weight) in self.pitches.iter() {
+            if roll < *weight {
+                return *pitch_type;
+            }
+            roll -= weight;
+        }
+        PitchType::Fastball
+    }
+}
+
+impl Default for PitchDeck {
+    fn default() -> Self {
+        Self::presets().remove(0)
+    }
+}
+
+// finished runs, aim assist tapers off over the first few
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Onboarding {
+    pub(crate) runs: u32,
+    pub(crate) aim_assist: bool,
+}
+
+impl Default for Onboarding {
+    fn default() -> Self {
+        Self {
+            runs: 0,
+            aim_assist: true,
+        }
+    }
+}
+
+// the last RUN_HISTORY_LENGTH counted runs, oldest first
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct RunHistory(pub(crate) VecDeque<PastRun>);
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PastRun {
+    pub(crate) score: u32,
+    // seconds since the unix epoch
+    pub(crate) timestamp: u64,
+    pub(crate) seed: u64,
+}
+
+// hit radius scale, 1.0 is no assist, also slows pitches
+pub(crate) struct AimAssist(pub(crate) f32);
+
+// recent swing outcomes the assist adapts to
+#[derive(Default)]
+pub(crate) struct RecentSwings(pub(crate) VecDeque<bool>);
+
+// gameplay randomness, seeded so headless runs repeat
+pub(crate) struct GameRng(pub(crate) StdRng);
+
+// the demo batter's own randomness, so the pitches come out the same whoever is batting
+pub(crate) struct AiRng(pub(crate) StdRng);
+
+impl AiRng {
+    // flipped so it doesn't mirror GameRng
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(!seed))
+    }
+}
+
+// what GameRng was seeded with this run, for share codes
+pub(crate) struct GameSeed(pub(crate) u64);
+
+// one frame of a run, its length in SHARE_DT_MICROS and the bat aim quantized to -127..127
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct InputFrame {
+    pub(crate) dt: u16,
+    pub(crate) aim: [i8; 2],
+}
+
+// every frame of the current run
+#[derive(Default)]
+pub(crate) struct InputRecording(pub(crate) Vec<InputFrame>);
+
+// recorded frames driving the clock and the bat while watching a shared run
+#[derive(Default)]
+pub(crate) struct ReplayInput(pub(crate) Option<Vec<InputFrame>>);
+
+// the game's own clock, copied over Time every frame by drive_sim_clock
+#[derive(Default)]
+pub(crate) struct SimClock {
+    pub(crate) time: Option<Time>,
+    pub(crate) last_real: Option<Instant>,
+    // wall clock seconds since the last frame, for the cameras that move while the game holds still
+    pub(crate) real_delta: f32,
+    // index into InputRecording while this frame is part of a run
+    pub(crate) frame: Option<usize>,
+    // a new game counts its first frame even before the state has changed over
+    pub(crate) run_started: bool,
+}
+
+// fixed step accumulators by name, cleared at the start of every run so replays step in phase
+#[derive(Default)]
+pub(crate) struct FixedSteps(pub(crate) HashMap<&'static str, f64>);
+
+impl FixedSteps {
+    pub(crate) fn accumulator(&self, name: &str) -> f64 {
+        self.0.get(name).copied().unwrap_or(0.0)
+    }
+}
+
+// text field for pasting a share code
+#[derive(Default)]
+pub(crate) struct ShareCodeInput {
+    pub(crate) active: bool,
+    pub(crate) text: String,
+}
+
+// a run anyone can watch: the seed plus every frame
+#[derive(PartialEq, Debug)]
+pub(crate) struct ShareCode {
+    pub(crate) seed: u64,
+    pub(crate) frames: Vec<InputFrame>,
+}
+
+pub(crate) struct BallAssets {
+    pub(crate) mesh: Handle<Mesh>,
+    pub(crate) material: Handle<StandardMaterial>,
+    // user supplied mesh, used once loaded and measured
+    pub(crate) custom_mesh: Option<Handle<Mesh>>,
+    pub(crate) custom_scale: Option<f32>,
+}
+
+// optional gltf ball, e.g. "ball.glb" in assets, icosphere otherwise
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct BallConfig {
+    pub(crate) mesh_path: Option<String>,
+}
+
+// what the bat looks like, colliders are laid out separately by BatColliderCount
+// offsets are from the bat's centre, which shrinks along with it
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct BatVisualConfig {
+    pub(crate) parts: Vec<BatPartConfig>,
+}
+
+impl Default for BatVisualConfig {
+    fn default() -> Self {
+        Self {
+            parts: vec![BatPartConfig {
+                shape: BatPartShape::Capsule {
+                    radius: 0.1,
+                    depth: 1.0,
+                },
+                offset: Vec3::ZERO,
+                color: Color::WHITE,
+            }],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct BatPartConfig {
+    pub(crate) shape: BatPartShape,
+    pub(crate) offset: Vec3,
+    pub(crate) color: Color,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) enum BatPartShape {
+    Capsule { radius: f32, depth: f32 },
+    Box { size: Vec3 },
+    Sphere { radius: f32 },
+    // gltf in assets, e.g. "bat_logo.glb"
+    Mesh { path: String },
+}
+
+pub(crate) struct ParticleAssets {
+    pub(crate) mesh: Handle<Mesh>,
+    // one unlit material per color, keyed by rgba
+    pub(crate) materials: HashMap<u32, Handle<StandardMaterial>>,
+}
+
+// expired particles waiting to be reused
+#[derive(Default)]
+pub(crate) struct ParticlePool(pub(crate) Vec<Entity>);
+
+pub(crate) struct ParticleSpec {
+    pub(crate) position: Vec3,
+    pub(crate) direction: Vec3,
+    pub(crate) count: u32,
+    pub(crate) spread: f32,
+    pub(crate) speed: f32,
+    pub(crate) size: f32,
+    pub(crate) color: Color,
+    pub(crate) gravity: f32,
+    pub(crate) lifetime: f32,
+}
+
+// every hotkey, systems read from here so the help overlay stays accurate
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct KeyBindings {
+    pub(crate) help: KeyCode,
+    pub(crate) rebind: KeyCode,
+    pub(crate) restart: KeyCode,
+    pub(crate) toggle_bat_mode: KeyCode,
+    pub(crate) cycle_bat_weight: KeyCode,
+    pub(crate) toggle_targets: KeyCode,
+    pub(crate) toggle_no_tells: KeyCode,
+    pub(crate) toggle_cinematic_hits: KeyCode,
+    pub(crate) toggle_hit_stop: KeyCode,
+    pub(crate) toggle_next_ball_highlight: KeyCode,
+    pub(crate) toggle_reduce_motion: KeyCode,
+    pub(crate) share_run: KeyCode,
+    pub(crate) watch_run: KeyCode,
+    pub(crate) toggle_aim_assist: KeyCode,
+    pub(crate) toggle_gravity_wells: KeyCode,
+    pub(crate) toggle_backboard: KeyCode,
+    pub(crate) study_freeze: KeyCode,
+    pub(crate) cycle_pitch_deck: KeyCode,
+    pub(crate) more_fastballs: KeyCode,
+    pub(crate) more_curveballs: KeyCode,
+    pub(crate) more_changeups: KeyCode,
+    pub(crate) toggle_ai: KeyCode,
+    pub(crate) toggle_mute: KeyCode,
+    pub(crate) toggle_heatmap: KeyCode,
+    pub(crate) toggle_vsync: KeyCode,
+    pub(crate) cycle_frame_cap: KeyCode,
+    pub(crate) toggle_frame_graph: KeyCode,
+    pub(crate) photo_mode: KeyCode,
+    pub(crate) settings: KeyCode,
+    pub(crate) cycle_camera_preset: KeyCode,
+    pub(crate) toggle_co_op: KeyCode,
+    pub(crate) toggle_curve_arc: KeyCode,
+    pub(crate) toggle_strike_zone: KeyCode,
+    pub(crate) toggle_pitch_difficulty: KeyCode,
+    pub(crate) reset_heatmap: KeyCode,
+    pub(crate) fewer_bat_colliders: KeyCode,
+    pub(crate) more_bat_colliders: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) toggle_step_mode: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) step: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) toggle_gizmos: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) toggle_spawner: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) cycle_spawn_speed: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) slower_time: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) faster_time: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) toggle_colliders: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) shrink_colliders: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) grow_colliders: KeyCode,
+    #[cfg(feature = "debug")]
+    pub(crate) save_physics: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            help: KeyCode::F1,
+            rebind: KeyCode::F2,
+            restart: KeyCode::R,
+            toggle_bat_mode: KeyCode::B,
+            cycle_bat_weight: KeyCode::W,
+            toggle_targets: KeyCode::T,
+            toggle_no_tells: KeyCode::N,
+            toggle_cinematic_hits: KeyCode::C,
+            toggle_hit_stop: KeyCode::L,
+            toggle_next_ball_highlight: KeyCode::O,
+            toggle_reduce_motion: KeyCode::M,
+            share_run: KeyCode::F5,
+            watch_run: KeyCode::F6,
+            toggle_aim_assist: KeyCode::A,
+            toggle_gravity_wells: KeyCode::V,
+            toggle_backboard: KeyCode::E,
+            study_freeze: KeyCode::Space,
+            cycle_pitch_deck: KeyCode::D,
+            more_fastballs: KeyCode::Key1,
+            more_curveballs: KeyCode::Key2,
+            more_changeups: KeyCode::Key3,
+            toggle_ai: KeyCode::I,
+            toggle_mute: KeyCode::F3,
+            toggle_heatmap: KeyCode::H,
+            toggle_vsync: KeyCode::F4,
+            cycle_frame_cap: KeyCode::F9,
+            toggle_frame_graph: KeyCode::F7,
+            photo_mode: KeyCode::F8,
+            settings: KeyCode::F12,
+            cycle_camera_preset: KeyCode::Tab,
+            toggle_co_op: KeyCode::U,
+            toggle_curve_arc: KeyCode::Y,
+            toggle_strike_zone: KeyCode::Z,
+            toggle_pitch_difficulty: KeyCode::X,
+            reset_heatmap: KeyCode::Back,
+            fewer_bat_colliders: KeyCode::LBracket,
+            more_bat_colliders: KeyCode::RBracket,
+            #[cfg(feature = "debug")]
+            toggle_step_mode: KeyCode::P,
+            #[cfg(feature = "debug")]
+            step: KeyCode::Period,
+            #[cfg(feature = "debug")]
+            toggle_gizmos: KeyCode::G,
+            #[cfg(feature = "debug")]
+            toggle_spawner: KeyCode::J,
+            #[cfg(feature = "debug")]
+            cycle_spawn_speed: KeyCode::K,
+            #[cfg(feature = "debug")]
+            slower_time: KeyCode::Minus,
+            #[cfg(feature = "debug")]
+            faster_time: KeyCode::Equals,
+            #[cfg(feature = "debug")]
+            toggle_colliders: KeyCode::F,
+            #[cfg(feature = "debug")]
+            shrink_colliders: KeyCode::Semicolon,
+            #[cfg(feature = "debug")]
+            grow_colliders: KeyCode::Apostrophe,
+            #[cfg(feature = "debug")]
+            save_physics: KeyCode::F10,
+        }
+    }
+}
+
+impl KeyBindings {
+    pub(crate) fn entries_mut(&mut self) -> Vec<(&'static str, &mut KeyCode)> {
+        let entries = vec![
+            ("help", &mut self.help),
+            ("rebind keys", &mut self.rebind),
+            ("restart", &mut self.restart),
+            ("rigid/floppy bat", &mut self.toggle_bat_mode),
+            ("bat weight", &mut self.cycle_bat_weight),
+            ("target challenge", &mut self.toggle_targets),
+            ("pitch colors", &mut self.toggle_no_tells),
+            ("cinematic hits", &mut self.toggle_cinematic_hits),
+            ("global/local hit-stop", &mut self.toggle_hit_stop),
+            ("next ball highlight", &mut self.toggle_next_ball_highlight),
+            ("reduce motion", &mut self.toggle_reduce_motion),
+            ("share run", &mut self.share_run),
+            ("watch shared run", &mut self.watch_run),
+            ("aim assist", &mut self.toggle_aim_assist),
+            ("gravity wells", &mut self.toggle_gravity_wells),
+            ("backboard", &mut self.toggle_backboard),
+            ("freeze pitch (hold)", &mut self.study_freeze),
+            ("pitch deck", &mut self.cycle_pitch_deck),
+            ("custom deck: fastballs", &mut self.more_fastballs),
+            ("custom deck: curveballs", &mut self.more_curveballs),
+            ("custom deck: changeups", &mut self.more_changeups),
+            ("demo batter", &mut self.toggle_ai),
+            ("mute", &mut self.toggle_mute),
+            ("hit heatmap", &mut self.toggle_heatmap),
+            ("reset heatmap", &mut self.reset_heatmap),
+            ("vsync", &mut self.toggle_vsync),
+            ("frame cap", &mut self.cycle_frame_cap),
+            ("frame time graph", &mut self.toggle_frame_graph),
+            ("photo mode", &mut self.photo_mode),
+            ("settings", &mut self.settings),
+            ("camera distance", &mut self.cycle_camera_preset),
+            ("co-op bat", &mut self.toggle_co_op),
+            ("curveball path", &mut self.toggle_curve_arc),
+            ("strike zone", &mut self.toggle_strike_zone),
+            ("pitch difficulty", &mut self.toggle_pitch_difficulty),
+            ("fewer bat points", &mut self.fewer_bat_colliders),
+            ("more bat points", &mut self.more_bat_colliders),
+        ];
+
+        #[cfg(feature = "debug")]
+        let entries = {
+            let mut entries = entries;
+            entries.extend([
+                ("step mode", &mut self.toggle_step_mode),
+                ("step frame", &mut self.step),
+                ("gizmos", &mut self.toggle_gizmos),
+                ("click to spawn", &mut self.toggle_spawner),
+                ("spawn speed", &mut self.cycle_spawn_speed),
+                ("slower time", &mut self.slower_time),
+                ("faster time", &mut self.faster_time),
+                ("show colliders", &mut self.toggle_colliders),
+                ("shrink colliders", &mut self.shrink_colliders),
+                ("grow colliders", &mut self.grow_colliders),
+                ("save physics", &mut self.save_physics),
+            ]);
+            entries
+        };
+
+        entries
+    }
+
+    pub(crate) fn entries(&self) -> Vec<(&'static str, KeyCode)> {
+        self.clone()
+            .entries_mut()
+            .into_iter()
+            .map(|(action, key)| (action, *key))
+            .collect()
+    }
+}
+
+// index into KeyBindings::entries waiting for a new key
+#[derive(Default)]
+pub(crate) struct Rebinding(pub(crate) Option<usize>);
+
+// highlighted row of the settings menu, an index into SettingsItem::ALL
+#[derive(Default)]
+pub(crate) struct SettingsMenu {
+    pub(crate) selected: usize,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum SettingsItem {
+    Volume,
+    Mute,
+    ShakeIntensity,
+    ReduceMotion,
+    SwingGlow,
+    HotBalls,
+    GroundGrid,
+    Vsync,
+    FrameCap,
+    RenderScale,
+    HudScale,
+    Cursor,
+    MouseAim,
+    AimSensitivity,
+    CameraDistance,
+    PitchDifficulty,
+    AimAssist,
+    MatchLength,
+    RestartDelay,
+}
+
+impl SettingsItem {
+    pub(crate) const ALL: [SettingsItem; 19] = [
+        SettingsItem::Volume,
+        SettingsItem::Mute,
+        SettingsItem::ShakeIntensity,
+        SettingsItem::ReduceMotion,
+        SettingsItem::SwingGlow,
+        SettingsItem::HotBalls,
+        SettingsItem::GroundGrid,
+        SettingsItem::Vsync,
+        SettingsItem::FrameCap,
+        SettingsItem::RenderScale,
+        SettingsItem::HudScale,
+        SettingsItem::Cursor,
+        SettingsItem::MouseAim,
+        SettingsItem::AimSensitivity,
+        SettingsItem::CameraDistance,
+        SettingsItem::PitchDifficulty,
+        SettingsItem::AimAssist,
+        SettingsItem::MatchLength,
+        SettingsItem::RestartDelay,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            SettingsItem::Volume => "Volume",
+            SettingsItem::Mute => "Mute",
+            SettingsItem::ShakeIntensity => "Camera shake",
+            SettingsItem::ReduceMotion => "Reduce motion",
+            SettingsItem::SwingGlow => "Swing glow",
+            SettingsItem::HotBalls => "Hot balls",
+            SettingsItem::GroundGrid => "Ground grid",
+            SettingsItem::Vsync => "Vsync",
+            SettingsItem::FrameCap => "Frame cap",
+            SettingsItem::RenderScale => "Render scale",
+            SettingsItem::HudScale => "HUD scale",
+            SettingsItem::Cursor => "Cursor",
+            SettingsItem::MouseAim => "Mouse aim",
+            SettingsItem::AimSensitivity => "Aim sensitivity",
+            SettingsItem::CameraDistance => "Camera distance",
+            SettingsItem::PitchDifficulty => "Pitch difficulty colors",
+            SettingsItem::AimAssist => "Aim assist",
+            SettingsItem::MatchLength => "Match length",
+            SettingsItem::RestartDelay => "Restart delay",
+        }
+    }
+}
+
+// pitches pick a random radius in this range, smaller balls score more
+pub(crate) struct BallSize {
+    pub(crate) min: f32,
+    pub(crate) max: f32,
+}
+
+pub(crate) struct PitchPalette {
+    pub(crate) fastball: Handle<StandardMaterial>,
+    pub(crate) curveball: Handle<StandardMaterial>,
+    pub(crate) changeup: Handle<StandardMaterial>,
+    pub(crate) hazard: Handle<StandardMaterial>,
+    pub(crate) pressure: Handle<StandardMaterial>,
+    pub(crate) balloon: Handle<StandardMaterial>,
+    pub(crate) stone: Handle<StandardMaterial>,
+}
+
+// all balls white, pitch type has to be read from flight
+pub(crate) struct NoTells(pub(crate) bool);
+
+pub(crate) struct PitchOrigin {
+    pub(crate) position: Vec3,
+    pub(crate) flight_time: f32,
+}
+
+// box around the strike point, contact near its corners scores more
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct StrikeZone {
+    pub(crate) center: Vec3,
+    // half size across the plate, up, and toward the pitcher
+    pub(crate) bounds: Vec3,
+    // turns the box to face the batter
+    pub(crate) yaw: f32,
+    // score multiplier right in a corner, contact in the middle scores 1x
+    pub(crate) corner_multiplier: f32,
+}
+
+impl Default for StrikeZone {
+    fn default() -> Self {
+        Self {
+            center: vec3(4.3, 1.13, 4.3),
+            bounds: vec3(0.35, 0.4, 0.3),
+            yaw: std::f32::consts::FRAC_PI_4,
+            corner_multiplier: 2.0,
+        }
+    }
+}
+
+impl StrikeZone {
+    pub(crate) fn rotation(&self) -> Quat {
+        Quat::from_rotation_y(self.yaw)
+    }
+
+    // 1x outside the zone and in its middle, rising toward corner_multiplier in the corners
+    pub(crate) fn multiplier(&self, contact: Vec3) -> f32 {
+        let local = self.rotation().inverse() * (contact - self.center) / self.bounds;
+        if local.abs().max_element() > 1.0 {
+            return 1.0;
+        }
+        let corner = local.x.abs().min(local.y.abs());
+        1.0 + (self.corner_multiplier - 1.0) * corner
+    }
+}
+
+pub(crate) struct ShowStrikeZone(pub(crate) bool);
+
+// pitches start from a random origin and arrive at the strike point
+pub(crate) struct PitchConfig {
+    pub(crate) origins: Vec<PitchOrigin>,
+    pub(crate) strike_point: Vec3,
+    // odds a pitch is thrown well wide, there's no point swinging at those
+    pub(crate) wild_pitch_chance: f32,
+}
+
+pub(crate) struct Score(pub(crate) u32);
+
+// power hits in a row, broken by any other hit or an out
+// also broken when COMBO_WINDOW runs out without another one
+#[derive(Default)]
+pub(crate) struct Combo {
+    pub(crate) count: u32,
+    // seconds left, only ticks while the game runs
+    pub(crate) timer: f32,
+}
+
+// this run's hits, written out as a csv row at game over
+#[derive(Default)]
+pub(crate) struct Stats {
+    pub(crate) hits: u32,
+    pub(crate) power_hits: u32,
+    pub(crate) exit_speed_total: f32,
+    // sandbox was on at some point, the run doesn't count toward bests
+    pub(crate) sandbox: bool,
+}
+
+impl Stats {
+    pub(crate) fn average_exit_speed(&self) -> f32 {
+        self.exit_speed_total / self.hits.max(1) as f32
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct LastHit {
+    pub(crate) power: f32,
+    pub(crate) exit_speed: f32,
+    // degrees above the horizontal
+    pub(crate) launch_angle: f32,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct SessionBests {
+    pub exit_speed: f32,
+    pub launch_angle: f32,
+}
+
+pub(crate) struct BaseFov(pub(crate) f32);
+
+// seconds left on the last power hit's camera punch, eases out over PAUSE_TIME
+#[derive(Default)]
+pub(crate) struct FovPunchTimer(pub(crate) f32);
+
+// origin and type of the next pitch, picked a throw ahead so it can be telegraphed
+#[derive(Default)]
+pub(crate) struct NextPitch {
+    pub(crate) origin: Option<usize>,
+    pub(crate) pitch_type: Option<PitchType>,
+    pub(crate) wild: bool,
+}
+
+// glow around the pitch that reaches the plate soonest
+pub(crate) struct NextBallHighlight {
+    pub(crate) enabled: bool,
+    pub(crate) ball: Option<Entity>,
+}
+
+// predicted path of the curveball closest to the plate, off while pitch colors are hidden
+pub(crate) struct ShowCurveArc(pub(crate) bool);
+
+// marker at the next pitch's origin, timer counts down to the throw
+#[derive(Default)]
+pub(crate) struct SpawnWarning {
+    pub(crate) position: Vec3,
+    pub(crate) timer: f32,
+    // 0.0 is an easy pitch, 1.0 a nasty one
+    pub(crate) difficulty: f32,
+    pub(crate) wild: bool,
+}
+
+// tint the spawn warning by how hard the next pitch is
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ShowPitchDifficulty(pub(crate) bool);
+
+// radar gun readout of the last pitch to reach the plate
+#[derive(Default)]
+pub(crate) struct LastPitch {
+    pub(crate) speed: f32,
+    pub(crate) exit_speed: Option<f32>,
+    pub(crate) timer: f32,
+}
+
+pub(crate) struct TargetAssets {
+    pub(crate) idle: Handle<StandardMaterial>,
+    pub(crate) active: Handle<StandardMaterial>,
+}
+
+// target streak challenge: hit the highlighted targets in order
+#[derive(Default)]
+pub(crate) struct TargetSequence {
+    pub(crate) enabled: bool,
+    pub(crate) index: usize,
+    pub(crate) streak: u32,
+    pub(crate) timer: f32,
+}
+
+pub(crate) struct Rules {
+    pub(crate) outs_allowed: u32,
+}
+
+pub(crate) struct Outs(pub(crate) u32);
+
+// last 10% step the bat has shrunk past
+pub(crate) struct BatShrinkMilestone(pub(crate) u32);
+
+pub(crate) struct Inning {
+    pub(crate) number: u32,
+    pub(crate) pitches_left: u32,
+}
+
+impl Default for Inning {
+    fn default() -> Self {
+        Self {
+            number: 1,
+            pitches_left: PITCHES_PER_INNING,
+        }
+    }
+}
+
+// score earned in each finished inning
+#[derive(Default)]
+pub(crate) struct InningScores(pub(crate) Vec<u32>);
+
+pub(crate) struct Announcement {
+    pub(crate) text: String,
+    pub(crate) timer: f32,
+}
+
+// cheat for testing, bats reach SANDBOX_REACH times as far so every pitch gets hit
+pub(crate) struct Sandbox(pub(crate) bool);
+
+// pitches in a match, the game ends once the last one is dealt with
+#[derive(Serialize, Deserialize)]
+pub(crate) struct MatchLength(pub(crate) Option<u32>);
+
+impl MatchLength {
+    pub(crate) fn finished(&self, pitch_count: &PitchCount) -> bool {
+        self.0.map_or(false, |limit| pitch_count.0 >= limit)
+    }
+}
+
+// pitches thrown this game, extra balls from multi-ball rules don't count
+#[derive(Default)]
+pub(crate) struct PitchCount(pub(crate) u32);
+
+// seconds the game over screen ignores restart, so a key held from play doesn't skip it
+#[derive(Serialize, Deserialize)]
+pub(crate) struct RestartDelay(pub(crate) f32);
+
+// counts down from RestartDelay on game over
+#[derive(Default)]
+pub(crate) struct GameOverTimer(pub(crate) f32);
+
+// escape was pressed once on the game over screen, again quits
+#[derive(Default)]
+pub(crate) struct ConfirmQuit(pub(crate) bool);
+
+// debug single-stepping of physics
+#[cfg(feature = "debug")]
+pub(crate) struct StepMode(pub(crate) bool);
+
+#[cfg(feature = "debug")]
+#[derive(Default)]
+pub(crate) struct StepFrame {
+    pub(crate) count: u32,
+    pub(crate) advance: bool,
+}
+
+#[cfg(feature = "debug")]
+pub(crate) struct ShowGizmos(pub(crate) bool);
+
+#[cfg(feature = "debug")]
+pub(crate) struct ShowColliders(pub(crate) bool);
+
+// solid spheres for the collider radius, see-through ones for the hit radius of an average ball
+#[cfg(feature = "debug")]
+pub(crate) struct ColliderGizmoAssets {
+    pub(crate) mesh: Handle<Mesh>,
+    pub(crate) collider_material: Handle<StandardMaterial>,
+    pub(crate) hit_material: Handle<StandardMaterial>,
+}
+
+// click to drop a ball on the ground aimed at the strike point, instead of waiting for a pitch
+#[cfg(feature = "debug")]
+#[derive(Default)]
+pub(crate) struct DebugSpawner {
+    pub(crate) enabled: bool,
+    // index into DEBUG_SPAWN_FLIGHT_TIMES
+    pub(crate) flight_time: usize,
+}
+
+// contact counts over the session, rows run handle to tip in bat space
+pub(crate) struct HitHeatmap {
+    pub(crate) counts: [[u32; HEATMAP_COLS]; HEATMAP_ROWS],
+}
+
+impl Default for HitHeatmap {
+    fn default() -> Self {
+        Self {
+            counts: [[0; HEATMAP_COLS]; HEATMAP_ROWS],
+        }
+    }
+}
+
+pub(crate) struct ShowHeatmap(pub(crate) bool);
+
+// hud text and panel sizes are given at scale 1.0 and go through here, the font is
+// loaded with the hud
+#[derive(Serialize, Deserialize)]
+pub(crate) struct HudSettings {
+    pub(crate) scale: f32,
+    #[serde(skip)]
+    pub(crate) font: Handle<Font>,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            font: Handle::default(),
+        }
+    }
+}
+
+impl HudSettings {
+    pub(crate) fn text(&self, font_size: f32, color: Color) -> TextStyle {
+        TextStyle {
+            font: self.font.clone(),
+            font_size: font_size * self.scale,
+            color,
+        }
+    }
+
+    pub(crate) fn px(&self, value: f32) -> Val {
+        Val::Px(value * self.scale)
+    }
+}
+
+// fifo when on, immediate when off, browsers always vsync
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Vsync(pub(crate) bool);
+
+// frames per second to sleep down to, native only
+#[derive(Serialize, Deserialize, Default)]
+pub(crate) struct FrameCap(pub(crate) Option<f64>);
+
+// the world renders at this fraction of the window resolution and is stretched to fit,
+// the hud stays at full resolution
+#[derive(Serialize, Deserialize)]
+pub(crate) struct RenderScale(pub(crate) f32);
+
+// most recent frame times in seconds, newest last
+#[derive(Default)]
+pub(crate) struct FrameTimes(pub(crate) VecDeque<f32>);
+
+pub(crate) struct ShowFrameGraph(pub(crate) bool);
+
+// highest recent swing power, falling back at SWING_PEAK_DECAY
+#[derive(Default)]
+pub(crate) struct SwingPeak(pub(crate) f32);
+
+// something that happens to the game while a rule is active
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) enum GameEffect {
+    GravityScale(f32),
+    PitchSpeed(f32),
+    BatScale(f32),
+    MultiBall(u32),
+}
+
+impl GameEffect {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            GameEffect::GravityScale(scale) if *scale < 0.0 => "Gravity flipped",
+            GameEffect::GravityScale(scale) if *scale < 1.0 => "Low gravity",
+            GameEffect::GravityScale(_) => "Heavy gravity",
+            GameEffect::PitchSpeed(_) => "Speed up",
+            GameEffect::BatScale(_) => "Bat squeeze",
+            GameEffect::MultiBall(_) => "Multi-ball",
+        }
+    }
+}
+
+// every `every` seconds of game time, `effect` applies for `duration` seconds
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct ScheduledRule {
+    pub(crate) every: f32,
+    pub(crate) duration: f32,
+    pub(crate) effect: GameEffect,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct RuleSchedule {
+    pub(crate) rules: Vec<ScheduledRule>,
+    #[serde(skip)]
+    pub(crate) active: Vec<bool>,
+}
+
+impl Default for RuleSchedule {
+    fn default() -> Self {
+        let rule = |every, duration, effect| ScheduledRule {
+            every,
+            duration,
+            effect,
+        };
+        Self {
+            rules: vec![
+                rule(40.0, 10.0, GameEffect::GravityScale(0.5)),
+                rule(60.0, 10.0, GameEffect::PitchSpeed(1.3)),
+                rule(90.0, 10.0, GameEffect::MultiBall(1)),
+            ],
+            active: Vec::new(),
+        }
+    }
+}
+
+// combined result of the active rules, rebuilt every frame so expiry reverts it
+pub(crate) struct RuleEffects {
+    pub(crate) gravity_scale: f32,
+    pub(crate) pitch_speed: f32,
+    pub(crate) bat_scale: f32,
+    pub(crate) extra_balls: u32,
+}
+
+impl Default for RuleEffects {
+    fn default() -> Self {
+        Self {
+            gravity_scale: 1.0,
+            pitch_speed: 1.0,
+            bat_scale: 1.0,
+            extra_balls: 0,
+        }
+    }
+}
+
+// hit balls never leave the bat faster than this
+pub(crate) struct MaxBallSpeed(pub(crate) f32);
+
+// bounciness per surface, 1.0 keeps all speed and 0.0 stops dead
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct PhysicsConfig {
+    // vertical speed kept by a ground bounce
+    pub(crate) ground_restitution: f32,
+    // horizontal speed kept by a ground bounce, 1.0 for a frictionless skid
+    pub(crate) ground_tangent_restitution: f32,
+    pub(crate) target_restitution: f32,
+    // rolling deceleration as a fraction of gravity, 0.0 rolls forever
+    pub(crate) ground_friction: f32,
+    // hit power a swing needs for a power hit
+    pub(crate) power_hit_threshold: f32,
+    // slower bat colliders let the ball pass through, so a held still bat can't bunt
+    // 0.0 lets any contact hit, as it always did
+    pub(crate) min_swing_speed: f32,
+    // seconds a swing may arrive after the ball passed through the bat and still hit it
+    pub(crate) hit_leniency: f32,
+    // balls on the ground slower than rest_speed for rest_time seconds are cleared away
+    pub(crate) rest_speed: f32,
+    pub(crate) rest_time: f32,
+    // a ball is hit within its size plus both of these from a bat collider, before aim assist
+    pub(crate) collider_radius: f32,
+    pub(crate) hit_margin: f32,
+    pub(crate) gravity_model: GravityModel,
+}
+
+impl PhysicsConfig {
+    pub(crate) fn hit_radius(&self, ball_size: f32, sandbox: &Sandbox) -> f32 {
+        let reach = if sandbox.0 { SANDBOX_REACH } else { 1.0 };
+        (ball_size + self.collider_radius + self.hit_margin) * reach
+    }
+}
+
+// how gravity pulls on hit balls over their flight, pitches always use constant gravity
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub(crate) enum GravityModel {
+    Constant,
+    // starts at `start` times gravity and eases up to `end` times over `ramp` seconds
+    // for an arcade hang then drop
+    HangTime { start: f32, end: f32, ramp: f32 },
+}
+
+impl GravityModel {
+    pub(crate) fn scale(&self, flight_time: f32) -> f32 {
+        match *self {
+            GravityModel::Constant => 1.0,
+            GravityModel::HangTime { start, end, ramp } => {
+                let t = (flight_time / ramp.max(0.01)).clamp(0.0, 1.0);
+                start + (end - start) * t * t
+            }
+        }
+    }
+}
+
+impl Default for PhysicsConfig {
+    fn default() -> Self {
+        Self {
+            ground_restitution: 0.7,
+            ground_tangent_restitution: 0.9,
+            target_restitution: 0.5,
+            ground_friction: 0.6,
+            power_hit_threshold: 0.3,
+            min_swing_speed: 0.0,
+            hit_leniency: 0.06,
+            rest_speed: 0.1,
+            rest_time: 2.0,
+            collider_radius: 0.0,
+            hit_margin: 0.15,
+            gravity_model: GravityModel::Constant,
+        }
+    }
+}
+
+// meter grid on the ground, a depth cue for judging where balls come down
+#[derive(Serialize, Deserialize)]
+pub(crate) struct GroundGrid(pub(crate) bool);
+
+// power shown through ball colors instead of glow, hard hits flash hot and fast pitches saturate
+#[derive(Serialize, Deserialize)]
+pub(crate) struct HotBalls(pub(crate) bool);
+
+// how brightly the bat glows as it nears power hit speed, 0.0 disables
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SwingGlow(pub(crate) f32);
+
+// max camera shake on hits, 0.0 disables
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ShakeIntensity(pub(crate) f32);
+
+// 0..1, impacts stack it up and it drains over time, shake grows with its square
+#[derive(Default)]
+pub(crate) struct Trauma(pub(crate) f32);
+
+// what camera_shake added this frame, taken back off before anything else moves the camera
+#[derive(Default)]
+pub(crate) struct ShakeOffset {
+    pub(crate) translation: Vec3,
+    pub(crate) rotation: Quat,
+    // noise position, runs faster than real time
+    pub(crate) time: f32,
+}
+
+// index into CAMERA_PRESETS
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CameraPreset(pub(crate) usize);
+
+// what apply_camera_preset added this frame, eased toward the current preset
+#[derive(Default)]
+pub(crate) struct CameraPresetOffset {
+    pub(crate) translation: Vec3,
+    pub(crate) pitch: f32,
+}
+
+// accessibility, tones down every effect that moves the view
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ReduceMotion(pub(crate) bool);
+
+impl ReduceMotion {
+    pub(crate) fn scale(&self) -> f32 {
+        if self.0 {
+            0.25
+        } else {
+            1.0
+        }
+    }
+}
+
+// steady push on hit balls, shifts every WIND_SHIFT_TIME seconds
+pub(crate) struct Wind(pub(crate) Vec3);
+
+// pitches slow down within radius of the strike point, off by default
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SlowZone {
+    pub(crate) enabled: bool,
+    pub(crate) radius: f32,
+    pub(crate) factor: f32,
+}
+
+impl Default for SlowZone {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 0.8,
+            factor: 0.5,
+        }
+    }
+}
+
+// heavier bats follow the cursor slower but hit harder
+#[derive(Clone, Copy)]
+pub(crate) enum BatWeight {
+    Light,
+    Medium,
+    Heavy,
+}
+
+impl BatWeight {
+    pub(crate) fn follow_rate(self) -> f32 {
+        match self {
+            BatWeight::Light => 60.0,
+            BatWeight::Medium => 40.0,
+            BatWeight::Heavy => 20.0,
+        }
+    }
+
+    pub(crate) fn power(self) -> f32 {
+        match self {
+            BatWeight::Light => 0.8,
+            BatWeight::Medium => 1.0,
+            BatWeight::Heavy => 1.3,
+        }
+    }
+
+    pub(crate) fn next(self) -> Self {
+        match self {
+            BatWeight::Light => BatWeight::Medium,
+            BatWeight::Medium => BatWeight::Heavy,
+            BatWeight::Heavy => BatWeight::Light,
+        }
+    }
+}
+
+// collision points along the bat, both rigid anchors and floppy segments
+pub(crate) struct BatColliderCount(pub(crate) u32);
+
+pub(crate) struct BatColliderAssets {
+    pub(crate) anchor_mesh: Handle<Mesh>,
+    pub(crate) anchor_material: Handle<StandardMaterial>,
+    pub(crate) segment_mesh: Handle<Mesh>,
+    pub(crate) segment_material: Handle<StandardMaterial>,
+}
+
+pub(crate) struct BatConfig {
+    pub(crate) floppy: bool,
+    pub(crate) spring_stiffness: f32,
+    pub(crate) spring_damping: f32,
+}
+
+// power hits dolly the camera back to follow the ball instead of pausing
+pub(crate) struct CinematicHits(pub(crate) bool);
+
+// global pauses the whole game on power hits, local only freezes the struck ball
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum HitStopMode {
+    Global,
+    Local,
+}
+
+// slows the ball simulation down, 1.0 is normal speed
+pub(crate) struct TimeScale {
+    // ball cam slow motion, only scales the balls and fielders
+    pub(crate) slow_motion: f32,
+    // debug slider, scales everything that moves and stays 1.0 without the debug feature
+    pub(crate) debug: f32,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self {
+            slow_motion: 1.0,
+            debug: 1.0,
+        }
+    }
+}
+
+impl TimeScale {
+    pub(crate) fn simulation(&self) -> f32 {
+        self.slow_motion * self.debug
+    }
+}
+
+// broadcast style chase camera behind a home run ball
+#[derive(Default)]
+pub(crate) struct BallCam {
+    pub(crate) ball: Option<Entity>,
+    // real seconds left, unaffected by TimeScale
+    pub(crate) timer: f32,
+}
+
+// where the camera sat in the player before photo mode took it out
+#[derive(Default)]
+pub(crate) struct PhotoCamera {
+    pub(crate) parent: Option<Entity>,
+    pub(crate) transform: Transform,
+    pub(crate) yaw: f32,
+    pub(crate) pitch: f32,
+}
+
+// free camera input gathered before hotkeys see it, movement is in camera space
+#[derive(Default)]
+pub(crate) struct PhotoInput {
+    pub(crate) movement: Vec3,
+    pub(crate) look: Vec2,
+}
+
+#[derive(Default)]
+pub(crate) struct FollowHitBall {
+    pub(crate) ball: Option<Entity>,
+    pub(crate) last_position: Vec3,
+    pub(crate) timer: f32,
+}
+
+// world space camera pose at a point in the intro
+pub(crate) struct CameraKeyframe {
+    pub(crate) time: f32,
+    pub(crate) position: Vec3,
+    pub(crate) look_at: Vec3,
+}
+
+// flythrough played once on load, ending in the play pose
+pub(crate) struct CameraIntro {
+    pub(crate) keyframes: Vec<CameraKeyframe>,
+    pub(crate) elapsed: f32,
+    pub(crate) active: bool,
+}
+
+impl Default for CameraIntro {
+    fn default() -> Self {
+        let keyframe = |time, position, look_at| CameraKeyframe {
+            time,
+            position,
+            look_at,
+        };
+        Self {
+            keyframes: vec![
+                keyframe(0.0, vec3(-7.0, 6.0, 6.0), Vec3::ZERO),
+                keyframe(2.0, vec3(-6.0, 3.0, -2.0), vec3(-4.5, 2.0, -4.5)),
+                keyframe(3.5, vec3(4.0, 3.0, -6.0), Vec3::ZERO),
+                keyframe(5.0, vec3(5.0, 1.0, 5.0), Vec3::ZERO),
+            ],
+            elapsed: 0.0,
+            active: true,
+        }
+    }
+}
+
+// how far the camera turns toward incoming pitches, 0.0 disables
+pub(crate) struct CameraTracking {
+    pub(crate) intensity: f32,
+}
+
+// events
+// fresh game with this seed, replaying recorded frames if given
+pub(crate) struct NewGameEvent {
+    pub(crate) seed: u64,
+    pub(crate) replay: Option<Vec<InputFrame>>,
+}
+
+pub(crate) struct TargetHitEvent {
+    pub(crate) target: usize,
+    pub(crate) bounces: u32,
+}
+
+pub(crate) enum HitEvent {
+    Ball {
+        ball: Entity,
+        power_hit: bool,
+        exit_velocity: Vec3,
+        // ball position at contact, world space
+        contact: Vec3,
+    },
+    // a bomb pitch was hit
+    Hazard {
+        ball: Entity,
+    },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) enum AppState {
+    InGame,
+    HitPause,
+    InningBreak,
+    GameOver,
+    // pushed over any other state, everything holds still for the free camera
+    PhotoMode,
+    // pushed over any other state like photo mode, the game waits behind the menu
+    Settings,
+}
+
+// runs alongside AppState, aiming hands the controls from the bat to a fielder
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub(crate) enum FieldingState {
+    Off,
+    Aiming,
+}
+
+// the fly ball being chased and where it should come down
+#[derive(Default)]
+pub(crate) struct Fielding {
+    pub(crate) ball: Option<Entity>,
+    pub(crate) landing: Vec3,
+}
+
+// components
+#[derive(Default)]
+pub(crate) struct PauseTimer(pub(crate) f32);
+
+#[derive(Default)]
+pub(crate) struct InningBreakTimer(pub(crate) f32);
+
+#[derive(Component)]
+pub(crate) struct Bat;
+
+// centre of the bat, carries its scale and the collider anchors
+#[derive(Component)]
+pub(crate) struct BatVisual;
+
+// one piece of the bat's look, built from BatVisualConfig
+#[derive(Component)]
+pub(crate) struct BatVisualPart;
+
+#[derive(Component)]
+pub(crate) struct BatCollider(pub(crate) i32);
+
+// which player a bat and its pieces belong to, 0 is the mouse and 1 the gamepad
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct BatOwner(pub(crate) usize);
+
+// rigid collision point, fixed to the bat visual
+#[derive(Component)]
+pub(crate) struct BatAnchor(pub(crate) i32);
+
+// floppy collision point, springs toward its anchor in world space
+#[derive(Component)]
+pub(crate) struct BatSegment {
+    pub(crate) index: i32,
+    pub(crate) velocity: Vec3,
+}
+
+#[cfg(feature = "debug")]
+#[derive(Component)]
+pub(crate) struct StepText;
+
+#[cfg(feature = "debug")]
+#[derive(Component)]
+pub(crate) struct Gizmo;
+
+// follows a bat collider, the hit sphere is the see-through one
+#[cfg(feature = "debug")]
+#[derive(Component)]
+pub(crate) struct ColliderGizmo {
+    pub(crate) collider: Entity,
+    pub(crate) hit: bool,
+}
+
+#[derive(Component)]
+pub(crate) struct OutIcon(pub(crate) u32);
+
+// one segment of the stadium scoreboard, digit 0 is the rightmost
+#[derive(Component)]
+pub(crate) struct ScoreSegment {
+    pub(crate) digit: usize,
+    pub(crate) segment: usize,
+}
+
+#[derive(Component)]
+pub(crate) struct AnnouncementText;
+
+#[derive(Component)]
+pub(crate) struct GameOverText;
+
+#[derive(Component)]
+pub(crate) struct SettingsPanel;
+
+// index into SettingsItem::ALL
+#[derive(Component)]
+pub(crate) struct SettingsRow(pub(crate) usize);
+
+#[derive(Component)]
+pub(crate) struct TargetSequenceText;
+
+#[derive(Component)]
+pub(crate) struct MatchText;
+
+#[derive(Component)]
+pub(crate) struct HelpText;
+
+#[derive(Component)]
+pub(crate) struct RadarText;
+
+#[derive(Component)]
+pub(crate) struct WindText;
+
+#[derive(Component)]
+pub(crate) struct PitchDeckText;
+
+#[derive(Component)]
+pub(crate) struct ShareCodeText;
+
+#[derive(Component)]
+pub(crate) struct InningSummaryPanel;
+
+#[derive(Component)]
+pub(crate) struct InningSummaryText;
+
+// cleared the fence in the air
+#[derive(Component)]
+pub(crate) struct HomeRun;
+
+#[derive(Component)]
+pub(crate) struct HeatmapPanel;
+
+#[derive(Component)]
+pub(crate) struct FrameGraphPanel;
+
+#[derive(Component)]
+pub(crate) struct FrameGraphBar(pub(crate) usize);
+
+#[derive(Component)]
+pub(crate) struct FrameGraphText;
+
+#[derive(Component)]
+pub(crate) struct SwingMeterFill;
+
+#[derive(Component)]
+pub(crate) struct SwingMeterPeak;
+
+#[derive(Component)]
+pub(crate) struct ComboPanel;
+
+#[derive(Component)]
+pub(crate) struct ComboText;
+
+#[derive(Component)]
+pub(crate) struct ComboBarFill;
+
+#[derive(Component)]
+pub(crate) struct HeatmapCell {
+    pub(crate) row: usize,
+    pub(crate) col: usize,
+}
+
+// pivot of the stadium flag, turns with the wind
+#[derive(Component)]
+pub(crate) struct WindFlag;
+
+#[derive(Component)]
+pub(crate) struct SpawnWarningMarker;
+
+#[derive(Component)]
+pub(crate) struct NextBallMarker;
+
+// grows a new ball up to `scale`, collisions use the full Size the whole time
+#[derive(Component)]
+pub(crate) struct SpawnAnim {
+    pub(crate) timer: f32,
+    pub(crate) scale: Vec3,
+}
+
+// shrinks a cleaned up ball away from `scale` and then despawns it
+#[derive(Component)]
+pub(crate) struct DespawnAnim {
+    pub(crate) timer: f32,
+    pub(crate) scale: Vec3,
+}
+
+#[derive(Component)]
+pub(crate) struct CurveArcDot(pub(crate) usize);
+
+#[derive(Component)]
+pub(crate) struct SwingArcDot(pub(crate) usize);
+
+#[derive(Component)]
+pub(crate) struct Ground;
+
+// the camera and sprite that stretch a scaled down render over the window
+#[derive(Component)]
+pub(crate) struct RenderScaleBlit;
+
+// wireframe of the StrikeZone, shown with aim assist on
+#[derive(Component)]
+pub(crate) struct StrikeZoneMarker;
+
+#[derive(Component)]
+pub(crate) struct Target(pub(crate) usize);
+
+// pulls hit balls in, strongest at the center and fading out to radius
+#[derive(Component)]
+pub(crate) struct GravityWell {
+    pub(crate) strength: f32,
+    pub(crate) radius: f32,
+}
+
+// flat board that pitches ricochet off, facing `normal` and `half_extents` across its face
+#[derive(Component)]
+pub(crate) struct Backboard {
+    pub(crate) normal: Vec3,
+    pub(crate) half_extents: Vec2,
+    pub(crate) restitution: f32,
+}
+
+// ball has already gone through a target
+#[derive(Component)]
+pub(crate) struct ScoredTarget;
+
+#[derive(Component)]
+pub(crate) struct Particle {
+    pub(crate) velocity: Vec3,
+    pub(crate) lifetime: f32,
+    pub(crate) gravity: f32,
+}
+
+// seconds left before the entity is despawned
+#[derive(Component)]
+pub(crate) struct Lifetime(pub(crate) f32);
+
+// see-through copy of a struck ball, alpha is where its fade starts
+#[derive(Component)]
+pub(crate) struct Afterimage {
+    pub(crate) alpha: f32,
+}
+
+#[derive(Component)]
+pub(crate) struct Fielder {
+    pub(crate) speed: f32,
+    pub(crate) home: Vec3,
+}
+
+// steered by the player while fielding, not one of the fielders
+#[derive(Component)]
+pub(crate) struct PlayerFielder;
+
+// spot under the fly ball being chased
+#[derive(Component)]
+pub(crate) struct LandingMarker;
+
+#[derive(Component, Default)]
+pub(crate) struct Velocity(pub(crate) Vec3);
+
+#[derive(Component, Default)]
+pub(crate) struct Size(pub(crate) f32);
+
+#[derive(Component, Default)]
+pub(crate) struct GameTime(pub(crate) f32);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum BallStatus {
+    Thrown,
+    Hit,
+    Missed,
+}
+
+#[derive(Component)]
+pub(crate) struct Status(pub(crate) BallStatus);
+
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) enum PitchType {
+    Fastball,
+    Curveball,
+    Changeup,
+}
+
+impl PitchType {
+    pub(crate) const ALL: [PitchType; 3] = [
+        PitchType::Fastball,
+        PitchType::Curveball,
+        PitchType::Changeup,
+    ];
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            PitchType::Fastball => "fastball",
+            PitchType::Curveball => "curveball",
+            PitchType::Changeup => "changeup",
+        }
+    }
+
+    pub(crate) fn difficulty(&self) -> &'static str {
+        match self {
+            PitchType::Fastball => "hard",
+            PitchType::Curveball => "medium",
+            PitchType::Changeup => "easy",
+        }
+    }
+}
+
+// what the ball is made of, sets how hard gravity pulls on it and what hitting it is worth
+#[derive(Component, Clone, Copy, PartialEq)]
+pub(crate) enum BallKind {
+    Standard,
+    // floats in slowly and hangs forever once hit
+    Balloon,
+    // drops hard, pitched and hit
+    Stone,
+}
+
+impl BallKind {
+    pub(crate) fn gravity_scale(&self) -> f32 {
+        match self {
+            BallKind::Standard => 1.0,
+            BallKind::Balloon => 0.3,
+            BallKind::Stone => 2.5,
+        }
+    }
+
+    // harder to hit well, more points
+    pub(crate) fn score_scale(&self) -> f32 {
+        match self {
+            BallKind::Standard => 1.0,
+            BallKind::Balloon => 0.5,
+            BallKind::Stone => 2.0,
+        }
+    }
+}
+
+// bomb pitch, hitting it costs an out
+#[derive(Component)]
+pub(crate) struct Hazard;
+
+// struck hard, fading from HOT_BALL_COLOR back to `base` on its own material
+#[derive(Component)]
+pub(crate) struct HotBall {
+    pub(crate) timer: f32,
+    pub(crate) heat: f32,
+    pub(crate) base: Color,
+}
+
+// pressure ball, speeds up while thrown
+#[derive(Component)]
+pub(crate) struct Accelerating {
+    pub(crate) rate: f32,
+}
+
+// thrown well wide, letting it go by doesn't cost an out
+#[derive(Component)]
+pub(crate) struct WildPitch;
+
+// speed the pitch was thrown at
+#[derive(Component, Default)]
+pub(crate) struct PitchSpeed(pub(crate) f32);
+
+// constant extra acceleration, for breaking pitches
+#[derive(Component, Default)]
+pub(crate) struct Curve(pub(crate) Vec3);
+
+// pitch is inside the slow zone with its speed scaled down
+#[derive(Component, Default)]
+pub(crate) struct InSlowZone(pub(crate) bool);
+
+// seconds since the ball was hit, drives the gravity model
+#[derive(Component, Default)]
+pub(crate) struct FlightTime(pub(crate) f32);
+
+// seconds the ball has sat still on the ground
+#[derive(Component, Default)]
+pub(crate) struct RestTimer(pub(crate) f32);
+
+// seconds this ball stays frozen after being struck
+#[derive(Component, Default)]
+pub(crate) struct HitStop(pub(crate) f32);
+
+// where the pitch came nearest to the bat within reach, so a late swing can still hit it there
+#[derive(Component, Default)]
+pub(crate) struct ClosestApproach(pub(crate) Option<Approach>);
+
+pub(crate) struct Approach {
+    pub(crate) collider: Entity,
+    pub(crate) distance: f32,
+    pub(crate) position: Vec3,
+    pub(crate) velocity: Vec3,
+    // seconds since the approach
+    pub(crate) age: f32,
+}
+
+// ground bounces since the ball was hit, for ricochet bonuses
+#[derive(Component, Default)]
+pub(crate) struct BounceCount(pub(crate) u32);
+
+#[derive(Component)]
+pub(crate) struct HistoricVelocity {
+    pub(crate) previous_pos: Vec3,
+    pub(crate) decaying_vel: Vec3,
+}
+
+// system params
+// every setting the menu can change, each saved under its own config name
+#[derive(SystemParam)]
+pub(crate) struct Settings<'w, 's> {
+    pub(crate) audio: ResMut<'w, AudioSettings>,
+    pub(crate) shake_intensity: ResMut<'w, ShakeIntensity>,
+    pub(crate) reduce_motion: ResMut<'w, ReduceMotion>,
+    pub(crate) swing_glow: ResMut<'w, SwingGlow>,
+    pub(crate) hot_balls: ResMut<'w, HotBalls>,
+    pub(crate) ground_grid: ResMut<'w, GroundGrid>,
+    pub(crate) vsync: ResMut<'w, Vsync>,
+    pub(crate) frame_cap: ResMut<'w, FrameCap>,
+    pub(crate) render_scale: ResMut<'w, RenderScale>,
+    pub(crate) hud_settings: ResMut<'w, HudSettings>,
+    pub(crate) cursor_mode: ResMut<'w, CursorMode>,
+    pub(crate) mouse_aim: ResMut<'w, MouseAim>,
+    pub(crate) camera_preset: ResMut<'w, CameraPreset>,
+    pub(crate) show_pitch_difficulty: ResMut<'w, ShowPitchDifficulty>,
+    pub(crate) onboarding: ResMut<'w, Onboarding>,
+    pub(crate) match_length: ResMut<'w, MatchLength>,
+    pub(crate) restart_delay: ResMut<'w, RestartDelay>,
+    #[system_param(ignore)]
+    pub(crate) marker: PhantomData<&'s ()>,
+}
+
+impl<'w, 's> Settings<'w, 's> {
+    pub(crate) fn value(&self, item: SettingsItem) -> String {
+        let on_off = |on: bool| if on { "on" } else { "off" }.to_string();
+        match item {
+            SettingsItem::Volume => format!("{:.0}%", self.audio.master_volume * 100.0),
+            SettingsItem::Mute => on_off(self.audio.muted),
+            SettingsItem::ShakeIntensity => format!("{:.0}%", self.shake_intensity.0 * 100.0),
+            SettingsItem::ReduceMotion => on_off(self.reduce_motion.0),
+            SettingsItem::SwingGlow => format!("{:.0}%", self.swing_glow.0 * 100.0),
+            SettingsItem::HotBalls => on_off(self.hot_balls.0),
+            SettingsItem::GroundGrid => on_off(self.ground_grid.0),
+            SettingsItem::Vsync => on_off(self.vsync.0),
+            SettingsItem::FrameCap => self
+                .frame_cap
+                .0
+                .map_or("off".to_string(), |cap| format!("{:.0} fps", cap)),
+            SettingsItem::RenderScale => format!("{:.0}%", self.render_scale.0 * 100.0),
+            SettingsItem::HudScale => format!("{:.0}%", self.hud_settings.scale * 100.0),
+            SettingsItem::Cursor => self.cursor_mode.name().to_string(),
+            SettingsItem::MouseAim => {
+                let mode = if self.mouse_aim.relative {
+                    "relative"
+                } else {
+                    "absolute"
+                };
+                mode.to_string()
+            }
+            SettingsItem::AimSensitivity => format!("{:.2}x", self.mouse_aim.sensitivity),
+            SettingsItem::CameraDistance => CAMERA_PRESETS
+                [self.camera_preset.0.min(CAMERA_PRESETS.len() - 1)]
+            .0
+            .to_string(),
+            SettingsItem::PitchDifficulty => on_off(self.show_pitch_difficulty.0),
+            SettingsItem::AimAssist => on_off(self.onboarding.aim_assist),
+            SettingsItem::MatchLength => match self.match_length.0 {
+                Some(limit) => format!("{} pitches", limit),
+                None => "endless".to_string(),
+            },
+            SettingsItem::RestartDelay => format!("{:.1} s", self.restart_delay.0),
+        }
+    }
+
+    // direction is -1 for left and 1 for right, toggles flip either way
+    pub(crate) fn adjust(&mut self, item: SettingsItem, direction: i32) {
+        let step =
+            |value: f32, size: f32, max: f32| (value + size * direction as f32).clamp(0.0, max);
+        let cycle =
+            |index: usize, len: usize| (index as i32 + direction).rem_euclid(len as i32) as usize;
+        match item {
+            SettingsItem::Volume => {
+                self.audio.master_volume = step(self.audio.master_volume, 0.1, 1.0)
+            }
+            SettingsItem::Mute => self.audio.muted = !self.audio.muted,
+            SettingsItem::ShakeIntensity => {
+                self.shake_intensity.0 = step(self.shake_intensity.0, 0.1, 1.0)
+            }
+            SettingsItem::ReduceMotion => self.reduce_motion.0 = !self.reduce_motion.0,
+            SettingsItem::SwingGlow => self.swing_glow.0 = step(self.swing_glow.0, 0.25, 2.0),
+            SettingsItem::HotBalls => self.hot_balls.0 = !self.hot_balls.0,
+            SettingsItem::GroundGrid => self.ground_grid.0 = !self.ground_grid.0,
+            SettingsItem::Vsync => self.vsync.0 = !self.vsync.0,
+            SettingsItem::FrameCap => {
+                let index = FRAME_CAPS
+                    .iter()
+                    .position(|cap| *cap == self.frame_cap.0)
+                    .unwrap_or(0);
+                self.frame_cap.0 = FRAME_CAPS[cycle(index, FRAME_CAPS.len())];
+            }
+            SettingsItem::RenderScale => {
+                let index = RENDER_SCALES
+                    .iter()
+                    .position(|scale| *scale == self.render_scale.0)
+                    .unwrap_or(0);
+                self.render_scale.0 = RENDER_SCALES[cycle(index, RENDER_SCALES.len())];
+            }
+            SettingsItem::Cursor => {
+                let index = CursorMode::ALL
+                    .iter()
+                    .position(|mode| *mode == *self.cursor_mode)
+                    .unwrap_or(0);
+                *self.cursor_mode = CursorMode::ALL[cycle(index, CursorMode::ALL.len())];
+            }
+            SettingsItem::HudScale => {
+                self.hud_settings.scale = step(self.hud_settings.scale, 0.25, 2.0).max(0.5)
+            }
+            SettingsItem::MouseAim => self.mouse_aim.relative = !self.mouse_aim.relative,
+            SettingsItem::AimSensitivity => {
+                self.mouse_aim.sensitivity = step(self.mouse_aim.sensitivity, 0.25, 4.0).max(0.25)
+            }
+            SettingsItem::CameraDistance => {
+                self.camera_preset.0 = cycle(self.camera_preset.0, CAMERA_PRESETS.len())
+            }
+            SettingsItem::PitchDifficulty => {
+                self.show_pitch_difficulty.0 = !self.show_pitch_difficulty.0
+            }
+            SettingsItem::AimAssist => self.onboarding.aim_assist = !self.onboarding.aim_assist,
+            SettingsItem::MatchLength => {
+                let index = MATCH_LENGTHS
+                    .iter()
+                    .position(|limit| *limit == self.match_length.0)
+                    .unwrap_or(0);
+                self.match_length.0 = MATCH_LENGTHS[cycle(index, MATCH_LENGTHS.len())];
+            }
+            SettingsItem::RestartDelay => {
+                self.restart_delay.0 = step(self.restart_delay.0, 0.5, 3.0)
+            }
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub(crate) struct Particles<'w, 's> {
+    pub(crate) commands: Commands<'w, 's>,
+    pub(crate) pool: ResMut<'w, ParticlePool>,
+    pub(crate) assets: ResMut<'w, ParticleAssets>,
+    pub(crate) materials: ResMut<'w, Assets<StandardMaterial>>,
+}
+
+// bundles
+#[derive(Bundle)]
+pub(crate) struct BallBundle {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub computed_visibility: ComputedVisibility,
+    pub velocity: Velocity,
+    pub size: Size,
+    pub status: Status,
+    pub pitch_type: PitchType,
+    pub kind: BallKind,
+    pub pitch_speed: PitchSpeed,
+    pub curve: Curve,
+    pub bounce_count: BounceCount,
+    pub in_slow_zone: InSlowZone,
+    pub hit_stop: HitStop,
+    pub flight_time: FlightTime,
+    pub rest_timer: RestTimer,
+    pub closest_approach: ClosestApproach,
+}
+
+impl Default for BallBundle {
+    fn default() -> Self {
+        Self {
+            mesh: Default::default(),
+            material: Default::default(),
+            transform: Default::default(),
+            global_transform: Default::default(),
+            visibility: Default::default(),
+            computed_visibility: Default::default(),
+            velocity: Default::default(),
+            size: Default::default(),
+            status: Status(BallStatus::Thrown),
+            pitch_type: PitchType::Fastball,
+            kind: BallKind::Standard,
+            pitch_speed: Default::default(),
+            curve: Default::default(),
+            bounce_count: Default::default(),
+            in_slow_zone: Default::default(),
+            hit_stop: Default::default(),
+            flight_time: Default::default(),
+            rest_timer: Default::default(),
+            closest_approach: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit_radius_tracks_ball_and_collider_radius() {
+        let mut config = PhysicsConfig::default();
+        let sandbox = Sandbox(false);
+        let base = config.hit_radius(BASE_BALL_SIZE, &sandbox);
+
+        // one to one with the ball radius
+        let bigger_ball = config.hit_radius(BASE_BALL_SIZE * 2.0, &sandbox);
+        assert!((bigger_ball - base - BASE_BALL_SIZE).abs() < 1e-6);
+
+        // and with the collider radius
+        config.collider_radius += 0.1;
+        let bigger_collider = config.hit_radius(BASE_BALL_SIZE, &sandbox);
+        assert!((bigger_collider - base - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+// settings persistence, json files natively and localStorage on wasm
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn load_config<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let json = std::fs::read_to_string(format!("{}.json", name)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn save_config<T: Serialize>(name: &str, value: &T) {
+    let result = serde_json::to_string_pretty(value)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(format!("{}.json", name), json).map_err(|e| e.to_string()));
+
+    if let Err(e) = result {
+        warn!("failed to save {}: {}", name, e);
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+// one row per call, the header goes in first when the file is new
+#[cfg(not(target_family = "wasm"))]
+pub(crate) fn append_csv(name: &str, header: &str, row: &str) {
+    use std::io::Write;
+
+    let path = format!("{}.csv", name);
+    let result = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| {
+            if file.metadata()?.len() == 0 {
+                writeln!(file, "{}", header)?;
+            }
+            writeln!(file, "{}", row)
+        });
+
+    if let Err(e) = result {
+        warn!("failed to write {}: {}", path, e);
+    }
+}
+
+// SystemTime panics in the browser
+#[cfg(target_family = "wasm")]
+pub(crate) fn unix_time() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+pub(crate) fn load_config<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let json = local_storage()?.get_item(name).ok()??;
+    serde_json::from_str(&json).ok()
+}
+
+#[cfg(target_family = "wasm")]
+pub(crate) fn save_config<T: Serialize>(name: &str, value: &T) {
+    let saved = match (local_storage(), serde_json::to_string(value)) {
+        (Some(storage), Ok(json)) => storage.set_item(name, &json).is_ok(),
+        _ => false,
+    };
+
+    if !saved {
+        warn!("failed to save {}", name);
+    }
+}
+
+// a download can't be appended to, so rows collect in localStorage and the whole file downloads
+#[cfg(target_family = "wasm")]
+pub(crate) fn append_csv(name: &str, header: &str, row: &str) {
+    let storage = local_storage();
+    let csv = storage
+        .as_ref()
+        .and_then(|storage| storage.get_item(name).ok()?)
+        .unwrap_or_else(|| format!("{}\n", header));
+    let csv = format!("{}{}\n", csv, row);
+
+    let stored = storage.map_or(false, |storage| storage.set_item(name, &csv).is_ok());
+    if !stored {
+        warn!("failed to save {}", name);
+    }
+    if download(&format!("{}.csv", name), &csv).is_none() {
+        warn!("failed to download {}", name);
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn download(file_name: &str, contents: &str) -> Option<()> {
+    use wasm_bindgen::JsCast;
+
+    let link = web_sys::window()?
+        .document()?
+        .create_element("a")
+        .ok()?
+        .dyn_into::<web_sys::HtmlElement>()
+        .ok()?;
+    let encoded: String = contents
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+    link.set_attribute("href", &format!("data:text/csv;charset=utf-8,{}", encoded))
+        .ok()?;
+    link.set_attribute("download", file_name).ok()?;
+    link.click();
+    Some(())
+}
@@ -0,0 +1,399 @@
+use crate::{
+    components::*,
+    config::save_config,
+    physics::{pitch_velocity, sample_historic_velocity},
+};
+use bevy::prelude::*;
+
+// physics step mode and axis gizmos, only with the debug feature
+pub(crate) struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_step_text)
+            .add_system_to_stage(CoreStage::PreUpdate, step_mode_input)
+            .add_system(step_collider_historic_velocity)
+            .add_system(update_step_text)
+            .insert_resource(ShowGizmos(false))
+            .add_startup_system_to_stage(StartupStage::PostStartup, setup_gizmos)
+            .add_system(toggle_gizmos)
+            .insert_resource(DebugSpawner::default())
+            .add_system(debug_spawner_input)
+            .add_system(debug_time_scale_input)
+            .insert_resource(ShowColliders(false))
+            .add_startup_system(setup_collider_gizmos)
+            .add_system(tune_colliders)
+            .add_system(update_collider_gizmos)
+            .add_system(debug_spawn_ball);
+    }
+}
+
+fn setup_step_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("fonts/DejaVuSans-Bold.ttf"),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(5.0),
+                    left: Val::Px(5.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(StepText);
+}
+
+fn step_mode_input(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut step_mode: ResMut<StepMode>,
+    mut step_frame: ResMut<StepFrame>,
+) {
+    if keys.just_pressed(key_bindings.toggle_step_mode) {
+        step_mode.0 = !step_mode.0;
+    }
+
+    step_frame.advance = step_mode.0 && keys.just_pressed(key_bindings.step);
+    if step_frame.advance {
+        step_frame.count += 1;
+    }
+}
+
+fn step_collider_historic_velocity(
+    step_frame: Res<StepFrame>,
+    mut q: Query<(&BatCollider, &GlobalTransform, &mut HistoricVelocity)>,
+) {
+    if !step_frame.advance {
+        return;
+    }
+
+    for (_collider, global_transform, mut historical_velocity) in q.iter_mut() {
+        sample_historic_velocity(global_transform, &mut historical_velocity);
+    }
+}
+
+fn update_step_text(
+    step_mode: Res<StepMode>,
+    step_frame: Res<StepFrame>,
+    debug_spawner: Res<DebugSpawner>,
+    time_scale: Res<TimeScale>,
+    show_colliders: Res<ShowColliders>,
+    physics_config: Res<PhysicsConfig>,
+    mut q: Query<&mut Text, With<StepText>>,
+) {
+    let mut value = if step_mode.0 {
+        format!("step frame {}", step_frame.count)
+    } else {
+        String::new()
+    };
+    if debug_spawner.enabled {
+        value += &format!(
+            "\nclick to spawn, {} s to the plate",
+            DEBUG_SPAWN_FLIGHT_TIMES[debug_spawner.flight_time]
+        );
+    }
+    if time_scale.debug != 1.0 {
+        value += &format!("\ntime {:.2}x", time_scale.debug);
+    }
+    if show_colliders.0 {
+        value += &format!(
+            "\ncollider radius {:.2}, hit margin {:.2}",
+            physics_config.collider_radius, physics_config.hit_margin
+        );
+    }
+    for mut text in q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn debug_spawner_input(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut debug_spawner: ResMut<DebugSpawner>,
+) {
+    if keys.just_pressed(key_bindings.toggle_spawner) {
+        debug_spawner.enabled = !debug_spawner.enabled;
+    }
+    if keys.just_pressed(key_bindings.cycle_spawn_speed) {
+        debug_spawner.flight_time =
+            (debug_spawner.flight_time + 1) % DEBUG_SPAWN_FLIGHT_TIMES.len();
+    }
+}
+
+// slow everything down to watch a collision, or speed it up to skip ahead
+fn debug_time_scale_input(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    let mut scale = time_scale.debug;
+    if keys.just_pressed(key_bindings.slower_time) {
+        scale /= DEBUG_TIME_SCALE_STEP;
+    }
+    if keys.just_pressed(key_bindings.faster_time) {
+        scale *= DEBUG_TIME_SCALE_STEP;
+    }
+    // snap back to exactly 1.0 when passing it
+    if (scale - 1.0).abs() < 0.05 {
+        scale = 1.0;
+    }
+    time_scale.debug = scale.clamp(DEBUG_TIME_SCALE_MIN, DEBUG_TIME_SCALE_MAX);
+}
+
+fn setup_collider_gizmos(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(ColliderGizmoAssets {
+        mesh: meshes.add(Mesh::from(shape::UVSphere {
+            radius: 1.0,
+            sectors: 16,
+            stacks: 8,
+        })),
+        collider_material: materials.add(StandardMaterial {
+            base_color: Color::rgb(1.0, 0.5, 0.0),
+            unlit: true,
+            ..default()
+        }),
+        hit_material: materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 1.0, 0.0, 0.2),
+            unlit: true,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+    });
+}
+
+// live tuning of the bat's reach, saved to the physics config on request
+fn tune_colliders(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut show_colliders: ResMut<ShowColliders>,
+    mut physics_config: ResMut<PhysicsConfig>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.toggle_colliders) {
+        show_colliders.0 = !show_colliders.0;
+    }
+    if !show_colliders.0 {
+        return;
+    }
+
+    let mut step = 0.0;
+    if keys.just_pressed(key_bindings.shrink_colliders) {
+        step -= DEBUG_COLLIDER_STEP;
+    }
+    if keys.just_pressed(key_bindings.grow_colliders) {
+        step += DEBUG_COLLIDER_STEP;
+    }
+    if step != 0.0 {
+        let value = if keys.pressed(KeyCode::LShift) {
+            &mut physics_config.collider_radius
+        } else {
+            &mut physics_config.hit_margin
+        };
+        *value = (*value + step).max(0.0);
+    }
+
+    if keys.just_pressed(key_bindings.save_physics) {
+        save_config("ld51_physics", &*physics_config);
+        announcement.text = "Physics config saved".to_string();
+        announcement.timer = 1.5;
+    }
+}
+
+// spheres ride along with every collider, rebuilt colliders get fresh ones
+fn update_collider_gizmos(
+    mut commands: Commands,
+    show_colliders: Res<ShowColliders>,
+    physics_config: Res<PhysicsConfig>,
+    ball_size: Res<BallSize>,
+    aim_assist: Res<AimAssist>,
+    sandbox: Res<Sandbox>,
+    collider_gizmo_assets: Res<ColliderGizmoAssets>,
+    q_colliders: Query<(Entity, &GlobalTransform), With<BatCollider>>,
+    mut q_gizmos: Query<(Entity, &ColliderGizmo, &mut Transform, &mut Visibility)>,
+) {
+    let average_ball = (ball_size.min + ball_size.max) * 0.5;
+    let radius = |hit: bool| {
+        if hit {
+            (physics_config.hit_radius(average_ball, &sandbox) - average_ball) * aim_assist.0
+        } else {
+            physics_config.collider_radius
+        }
+    };
+
+    let mut followed = Vec::new();
+    for (entity, gizmo, mut transform, mut visibility) in q_gizmos.iter_mut() {
+        match q_colliders.get(gizmo.collider) {
+            Ok((_, collider_transform)) => {
+                transform.translation = collider_transform.translation();
+                transform.scale = Vec3::splat(radius(gizmo.hit).max(0.001));
+                visibility.is_visible = show_colliders.0;
+                followed.push(gizmo.collider);
+            }
+            Err(_) => commands.entity(entity).despawn(),
+        }
+    }
+
+    if !show_colliders.0 {
+        return;
+    }
+
+    for (collider, _) in q_colliders.iter() {
+        if followed.contains(&collider) {
+            continue;
+        }
+        for hit in [false, true] {
+            commands
+                .spawn_bundle(PbrBundle {
+                    mesh: collider_gizmo_assets.mesh.clone(),
+                    material: if hit {
+                        collider_gizmo_assets.hit_material.clone()
+                    } else {
+                        collider_gizmo_assets.collider_material.clone()
+                    },
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                })
+                .insert(ColliderGizmo { collider, hit });
+        }
+    }
+}
+
+// cast the cursor through the camera onto the ground, snap it to the grid and spawn there
+fn debug_spawn_ball(
+    mut commands: Commands,
+    windows: Res<Windows>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    debug_spawner: Res<DebugSpawner>,
+    ball_assets: Res<BallAssets>,
+    ball_size: Res<BallSize>,
+    pitch_config: Res<PitchConfig>,
+    rule_effects: Res<RuleEffects>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+) {
+    if !debug_spawner.enabled || !mouse_buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let cursor_position = match window.cursor_position() {
+        Some(position) => position,
+        None => return,
+    };
+    let (camera, camera_transform) = match q_camera.get_single() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    // reverse z, ndc depth 1 is the near plane
+    let ndc = cursor_position / Vec2::new(window.width(), window.height()) * 2.0 - Vec2::ONE;
+    let ndc_to_world = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
+    let near = ndc_to_world.project_point3(ndc.extend(1.0));
+    let far = ndc_to_world.project_point3(ndc.extend(0.5));
+    let direction = (far - near).normalize_or_zero();
+
+    let radius = (ball_size.min + ball_size.max) * 0.5;
+    if direction.y >= 0.0 {
+        return;
+    }
+    let ground = near + direction * ((radius - near.y) / direction.y);
+    let position = Vec3::new(
+        (ground.x / DEBUG_SPAWN_GRID).round() * DEBUG_SPAWN_GRID,
+        radius,
+        (ground.z / DEBUG_SPAWN_GRID).round() * DEBUG_SPAWN_GRID,
+    );
+
+    let velocity = pitch_velocity(
+        position,
+        pitch_config.strike_point,
+        DEBUG_SPAWN_FLIGHT_TIMES[debug_spawner.flight_time],
+        Vec3::ZERO,
+        GRAVITY * rule_effects.gravity_scale,
+    );
+    commands.spawn_bundle(BallBundle {
+        mesh: ball_assets.mesh.clone_weak(),
+        material: ball_assets.material.clone_weak(),
+        transform: Transform::from_translation(position).with_scale(Vec3::splat(radius)),
+        size: Size(radius),
+        velocity: Velocity(velocity),
+        pitch_speed: PitchSpeed(velocity.length()),
+        ..default()
+    });
+}
+
+fn setup_gizmos(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_bat: Query<Entity, With<Bat>>,
+) {
+    // thin boxes along +x, +y and +z
+    let axes = [
+        (Vec3::X, Color::RED),
+        (Vec3::Y, Color::GREEN),
+        (Vec3::Z, Color::BLUE),
+    ]
+    .map(|(axis, color)| {
+        let size = axis * 0.98 + Vec3::splat(0.02);
+        (
+            meshes.add(Mesh::from(shape::Box::new(size.x, size.y, size.z))),
+            materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true,
+                ..default()
+            }),
+            axis * 0.5,
+        )
+    });
+
+    let origin = commands.spawn_bundle(SpatialBundle::default()).id();
+    // the player's bat shows the rest rotation from update_bat_transform
+    let parents = [origin].into_iter().chain(q_bat.iter());
+
+    for parent in parents {
+        commands.entity(parent).with_children(|parent| {
+            for (mesh, material, offset) in axes.iter() {
+                parent
+                    .spawn_bundle(PbrBundle {
+                        mesh: mesh.clone(),
+                        material: material.clone(),
+                        transform: Transform::from_translation(*offset),
+                        visibility: Visibility { is_visible: false },
+                        ..default()
+                    })
+                    .insert(Gizmo);
+            }
+        });
+    }
+}
+
+fn toggle_gizmos(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut show_gizmos: ResMut<ShowGizmos>,
+    mut q: Query<&mut Visibility, With<Gizmo>>,
+) {
+    if !keys.just_pressed(key_bindings.toggle_gizmos) {
+        return;
+    }
+
+    show_gizmos.0 = !show_gizmos.0;
+    for mut visibility in q.iter_mut() {
+        visibility.is_visible = show_gizmos.0;
+    }
+}
@@ -0,0 +1,1297 @@
+use crate::{
+    components::*,
+    config::load_config,
+    physics::{bat_pose, predict_closest_pass},
+    spawn::{pitch_difficulty, pitch_flight_time},
+};
+use bevy::{
+    asset::LoadState,
+    math::{vec2, vec3},
+    prelude::*,
+    render::{
+        mesh::VertexAttributeValues,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    transform::TransformSystem,
+};
+
+// camera moves, particles and markers in the world
+pub(crate) struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::rgb(0.24, 0.44, 0.94)))
+            .insert_resource(FollowHitBall::default())
+            .insert_resource(CameraTracking { intensity: 0.15 })
+            .insert_resource(load_config("ld51_shake_intensity").unwrap_or(ShakeIntensity(0.5)))
+            .insert_resource(load_config("ld51_reduce_motion").unwrap_or(ReduceMotion(false)))
+            .insert_resource(load_config("ld51_swing_glow").unwrap_or(SwingGlow(1.0)))
+            .insert_resource(load_config("ld51_hot_balls").unwrap_or(HotBalls(false)))
+            .insert_resource(load_config("ld51_ground_grid").unwrap_or(GroundGrid(false)))
+            .add_system(apply_ground_grid)
+            .insert_resource(Trauma::default())
+            .insert_resource(BallCam::default())
+            .insert_resource(PhotoCamera::default())
+            .insert_resource(ShakeOffset::default())
+            .insert_resource(load_config("ld51_camera_preset").unwrap_or(CameraPreset(1)))
+            .insert_resource(CameraPresetOffset::default())
+            .insert_resource(ParticlePool::default())
+            .insert_resource(SpawnWarning::default())
+            .insert_resource(
+                load_config("ld51_pitch_difficulty").unwrap_or(ShowPitchDifficulty(true)),
+            )
+            .insert_resource(NextBallHighlight {
+                enabled: true,
+                ball: None,
+            })
+            .add_system_to_stage(CoreStage::PreUpdate, remove_camera_shake)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                remove_camera_preset.after(remove_camera_shake),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                apply_camera_preset.before(camera_shake),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                camera_shake.before(TransformSystem::TransformPropagate),
+            )
+            .add_system(add_hit_trauma)
+            .add_system(update_swing_glow)
+            .add_system_set(
+                // camera and effects follow the game, but only while it runs
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(update_particles)
+                    .with_system(start_follow_hit_ball)
+                    .with_system(follow_hit_ball)
+                    .with_system(start_ball_cam)
+                    .with_system(ball_cam)
+                    .with_system(trackball),
+            )
+            .add_system(load_custom_ball_mesh)
+            .add_system(spawn_particle_events)
+            .add_system(spawn_afterimages)
+            .add_system(fade_afterimages)
+            .add_system(pulse_pressure_balls)
+            .add_system(saturate_fast_pitches)
+            .add_system(heat_hit_balls)
+            .add_system(cool_hot_balls.after(heat_hit_balls))
+            .add_system(update_wind_flag)
+            .add_system(pick_next_ball)
+            .add_system(update_next_ball_marker.after(pick_next_ball))
+            .insert_resource(ShowCurveArc(false))
+            .add_system(update_curve_arc)
+            .insert_resource(ShowStrikeZone(true))
+            .add_system(update_strike_zone_marker)
+            .add_system(update_swing_arc)
+            .add_system(update_spawn_warning)
+            .add_system(update_spawn_warning_marker.after(update_spawn_warning))
+            .add_system(play_camera_intro)
+            // on the hit rather than the pause, local and cinematic hits don't pause the game
+            .insert_resource(FovPunchTimer::default())
+            .add_system(start_fov_punch)
+            .add_system(update_fov_punch.after(start_fov_punch))
+            .add_system_set(SystemSet::on_enter(AppState::PhotoMode).with_system(enter_photo_mode))
+            .add_system_set(SystemSet::on_update(AppState::PhotoMode).with_system(fly_photo_camera))
+            .add_system_set(SystemSet::on_exit(AppState::PhotoMode).with_system(exit_photo_mode));
+    }
+}
+
+fn add_hit_trauma(
+    last_hit: Res<LastHit>,
+    mut trauma: ResMut<Trauma>,
+    mut hit_events: EventReader<HitEvent>,
+) {
+    for event in hit_events.iter() {
+        trauma.0 += match event {
+            HitEvent::Ball { .. } => last_hit.power * TRAUMA_PER_POWER,
+            HitEvent::Hazard { .. } => HAZARD_TRAUMA,
+        };
+    }
+    trauma.0 = trauma.0.min(1.0);
+}
+
+// each bat heats up toward SWING_GLOW_COLOR as its fastest collider nears power hit speed
+fn update_swing_glow(
+    swing_glow: Res<SwingGlow>,
+    physics_config: Res<PhysicsConfig>,
+    bat_weight: Res<BatWeight>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q_colliders: Query<(&HistoricVelocity, &BatOwner), With<BatCollider>>,
+    q_visuals: Query<&BatOwner, With<BatVisual>>,
+    q_parts: Query<(&Parent, &Handle<StandardMaterial>), With<BatVisualPart>>,
+) {
+    let threshold = physics_config.power_hit_threshold.max(0.01);
+    for (parent, material) in q_parts.iter() {
+        let owner = match q_visuals.get(**parent) {
+            Ok(owner) => owner,
+            Err(_) => continue,
+        };
+        let power = q_colliders
+            .iter()
+            .filter(|(_, collider_owner)| *collider_owner == owner)
+            .map(|(historic_velocity, _)| {
+                historic_velocity.decaying_vel.length() * bat_weight.power()
+            })
+            .fold(0.0, f32::max);
+
+        let heat = (power / threshold).min(1.0) * swing_glow.0;
+        let emissive = SWING_GLOW_COLOR * heat * heat;
+        if materials
+            .get(material)
+            .map_or(false, |m| m.emissive != emissive)
+        {
+            if let Some(material) = materials.get_mut(material) {
+                material.emissive = emissive;
+            }
+        }
+    }
+}
+
+// pressure balls share one material, so they all pulse together
+fn pulse_pressure_balls(
+    time: Res<Time>,
+    pitch_palette: Res<PitchPalette>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q: Query<(), With<Accelerating>>,
+) {
+    if q.is_empty() {
+        return;
+    }
+
+    let pulse = 0.5 + 0.5 * (time.seconds_since_startup() as f32 * PRESSURE_PULSE_RATE).sin();
+    if let Some(material) = materials.get_mut(&pitch_palette.pressure) {
+        material.emissive = Color::ORANGE * pulse;
+    }
+}
+
+// fast pitches get their own material, lit in their own color by how hard they are
+fn saturate_fast_pitches(
+    hot_balls: Res<HotBalls>,
+    no_tells: Res<NoTells>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q: Query<
+        (&PitchSpeed, &PitchType, &mut Handle<StandardMaterial>),
+        (Added<Status>, Without<Hazard>, Without<Accelerating>),
+    >,
+) {
+    if !hot_balls.0 || no_tells.0 {
+        return;
+    }
+
+    for (pitch_speed, pitch_type, mut material) in q.iter_mut() {
+        let difficulty = pitch_difficulty(pitch_speed.0, *pitch_type);
+        if difficulty <= 0.0 {
+            continue;
+        }
+        if let Some(mut saturated) = materials.get(&material).cloned() {
+            saturated.emissive = saturated.base_color * difficulty * 0.6;
+            *material = materials.add(saturated);
+        }
+    }
+}
+
+// a hit ball flashes hot by its exit speed, on a material of its own so it fades alone
+fn heat_hit_balls(
+    mut commands: Commands,
+    hot_balls: Res<HotBalls>,
+    max_ball_speed: Res<MaxBallSpeed>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut hit_events: EventReader<HitEvent>,
+    mut q: Query<&mut Handle<StandardMaterial>, With<Status>>,
+) {
+    for event in hit_events.iter() {
+        if !hot_balls.0 {
+            continue;
+        }
+        if let HitEvent::Ball {
+            ball,
+            exit_velocity,
+            ..
+        } = *event
+        {
+            let mut material = match q.get_mut(ball) {
+                Ok(material) => material,
+                Err(_) => continue,
+            };
+            let mut hot = match materials.get(&material).cloned() {
+                Some(hot) => hot,
+                None => continue,
+            };
+            let heat = (exit_velocity.length() / max_ball_speed.0).min(1.0).powi(2);
+            let base = hot.base_color;
+            hot.base_color = lerp_color(base, HOT_BALL_COLOR, heat);
+            hot.emissive = HOT_BALL_COLOR * heat;
+            *material = materials.add(hot);
+            commands.entity(ball).insert(HotBall {
+                timer: HOT_BALL_TIME,
+                heat,
+                base,
+            });
+        }
+    }
+}
+
+fn cool_hot_balls(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q: Query<(Entity, &mut HotBall, &Handle<StandardMaterial>)>,
+) {
+    for (entity, mut hot_ball, material) in q.iter_mut() {
+        hot_ball.timer -= time.delta_seconds();
+        let heat = hot_ball.heat * (hot_ball.timer / HOT_BALL_TIME).max(0.0);
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color = lerp_color(hot_ball.base, HOT_BALL_COLOR, heat);
+            material.emissive = HOT_BALL_COLOR * heat;
+        }
+
+        if hot_ball.timer <= 0.0 {
+            commands.entity(entity).remove::<HotBall>();
+        }
+    }
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    Vec4::from(a).lerp(Vec4::from(b), t).into()
+}
+
+// the grid texture is built the first time it's needed and kept for toggling back on
+fn apply_ground_grid(
+    ground_grid: Res<GroundGrid>,
+    mut grid_image: Local<Option<Handle<Image>>>,
+    mut images: ResMut<Assets<Image>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q: Query<&Handle<StandardMaterial>, With<Ground>>,
+    q_added: Query<(), Added<Ground>>,
+) {
+    if !ground_grid.is_changed() && q_added.is_empty() {
+        return;
+    }
+
+    let texture = if ground_grid.0 {
+        Some(
+            grid_image
+                .get_or_insert_with(|| images.add(ground_grid_image()))
+                .clone(),
+        )
+    } else {
+        None
+    };
+    for material in q.iter() {
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color_texture = texture.clone();
+        }
+    }
+}
+
+// white with darker meter lines, tinted by the ground color, each line's edge is shaded by
+// how much of the pixel it covers so it stays smooth at a distance
+fn ground_grid_image() -> Image {
+    let size = GROUND_GRID_RESOLUTION;
+    let cell = size as f32 / GROUND_GRID_CELLS as f32;
+    let distance_to_line = |pixel: usize| {
+        let offset = (pixel as f32 + 0.5) % cell;
+        offset.min(cell - offset)
+    };
+
+    let mut data = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = distance_to_line(x).min(distance_to_line(y));
+            let coverage = (GROUND_GRID_LINE_WIDTH * 0.5 + 0.5 - distance).clamp(0.0, 1.0);
+            let shade = (255.0 * (1.0 - 0.35 * coverage)) as u8;
+            data.extend_from_slice(&[shade, shade, shade, 255]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: size as u32,
+            height: size as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn remove_camera_shake(
+    mut shake_offset: ResMut<ShakeOffset>,
+    mut q: Query<&mut Transform, With<Camera3d>>,
+) {
+    if let Ok(mut camera_transform) = q.get_single_mut() {
+        camera_transform.translation -= shake_offset.translation;
+        camera_transform.rotation = shake_offset.rotation.inverse() * camera_transform.rotation;
+    }
+    shake_offset.translation = Vec3::ZERO;
+    shake_offset.rotation = Quat::IDENTITY;
+}
+
+fn remove_camera_preset(
+    camera_preset_offset: Res<CameraPresetOffset>,
+    mut q: Query<&mut Transform, With<Camera3d>>,
+) {
+    if let Ok(mut camera_transform) = q.get_single_mut() {
+        camera_transform.translation -= camera_preset_offset.translation;
+        camera_transform.rotation *= Quat::from_rotation_x(-camera_preset_offset.pitch);
+    }
+}
+
+// the presets move the whole pose, so follow cams and shake all ride on top of it
+fn apply_camera_preset(
+    time: Res<Time>,
+    app_state: Res<State<AppState>>,
+    camera_preset: Res<CameraPreset>,
+    mut camera_preset_offset: ResMut<CameraPresetOffset>,
+    mut q: Query<&mut Transform, With<Camera3d>>,
+) {
+    let mut camera_transform = match q.get_single_mut() {
+        Ok(camera_transform) => camera_transform,
+        Err(_) => return,
+    };
+
+    // the photo camera flies free of the player
+    if *app_state.current() == AppState::PhotoMode {
+        *camera_preset_offset = CameraPresetOffset::default();
+        return;
+    }
+
+    let (_, translation, pitch) = CAMERA_PRESETS[camera_preset.0.min(CAMERA_PRESETS.len() - 1)];
+    let n = (time.delta_seconds() * CAMERA_PRESET_EASE).min(1.0);
+    camera_preset_offset.translation = camera_preset_offset.translation.lerp(translation, n);
+    camera_preset_offset.pitch += (pitch - camera_preset_offset.pitch) * n;
+
+    camera_transform.translation += camera_preset_offset.translation;
+    camera_transform.rotation *= Quat::from_rotation_x(camera_preset_offset.pitch);
+}
+
+// runs after everything else has placed the camera, so the shake rides on top
+fn camera_shake(
+    time: Res<Time>,
+    shake_intensity: Res<ShakeIntensity>,
+    reduce_motion: Res<ReduceMotion>,
+    mut trauma: ResMut<Trauma>,
+    mut shake_offset: ResMut<ShakeOffset>,
+    mut q: Query<&mut Transform, With<Camera3d>>,
+) {
+    trauma.0 = (trauma.0 - TRAUMA_DECAY * time.delta_seconds()).max(0.0);
+
+    let mut camera_transform = match q.get_single_mut() {
+        Ok(camera_transform) => camera_transform,
+        Err(_) => return,
+    };
+    let shake = trauma.0 * trauma.0 * shake_intensity.0 * reduce_motion.scale();
+    if shake <= 0.0 {
+        return;
+    }
+
+    shake_offset.time += time.delta_seconds() * SHAKE_FREQUENCY;
+    let t = shake_offset.time;
+    shake_offset.translation =
+        vec3(noise(t, 0), noise(t, 1), noise(t, 2)) * MAX_SHAKE_OFFSET * shake;
+    shake_offset.rotation = Quat::from_euler(
+        EulerRot::XYZ,
+        noise(t, 3) * MAX_SHAKE_ANGLE * shake,
+        noise(t, 4) * MAX_SHAKE_ANGLE * shake,
+        noise(t, 5) * MAX_SHAKE_ANGLE * shake,
+    );
+
+    camera_transform.translation += shake_offset.translation;
+    camera_transform.rotation = shake_offset.rotation * camera_transform.rotation;
+}
+
+// smooth 1d gradient noise in -1..1, each seed gives an unrelated curve
+fn noise(x: f32, seed: u32) -> f32 {
+    let gradient = |i: i32| {
+        let mut h = (i as u32).wrapping_mul(0x9e37_79b1) ^ seed.wrapping_mul(0x85eb_ca77);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x2c1b_3c6d);
+        h ^= h >> 12;
+        h as f32 / u32::MAX as f32 * 2.0 - 1.0
+    };
+
+    let i = x.floor();
+    let f = x - i;
+    let a = gradient(i as i32) * f;
+    let b = gradient(i as i32 + 1) * (f - 1.0);
+    let t = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+    (a + (b - a) * t) * 2.0
+}
+
+fn set_fov(q: &mut Query<&mut Projection, With<Camera3d>>, fov: f32) {
+    for mut projection in q.iter_mut() {
+        if let Projection::Perspective(ref mut perspective) = *projection {
+            perspective.fov = fov;
+        }
+    }
+}
+
+fn fov_punch(last_hit: &LastHit, reduce_motion: &ReduceMotion) -> f32 {
+    (last_hit.power * FOV_PUNCH).min(MAX_FOV_PUNCH) * reduce_motion.scale()
+}
+
+fn start_fov_punch(
+    mut hit_events: EventReader<HitEvent>,
+    mut fov_punch_timer: ResMut<FovPunchTimer>,
+) {
+    let power_hit = hit_events.iter().any(|event| {
+        matches!(
+            event,
+            HitEvent::Ball {
+                power_hit: true,
+                ..
+            }
+        )
+    });
+    if power_hit {
+        fov_punch_timer.0 = PAUSE_TIME;
+    }
+}
+
+fn update_fov_punch(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    base_fov: Res<BaseFov>,
+    last_hit: Res<LastHit>,
+    reduce_motion: Res<ReduceMotion>,
+    mut fov_punch_timer: ResMut<FovPunchTimer>,
+    mut q: Query<&mut Projection, With<Camera3d>>,
+) {
+    if fov_punch_timer.0 <= 0.0 {
+        return;
+    }
+    fov_punch_timer.0 = (fov_punch_timer.0 - time.delta_seconds() * time_scale.debug).max(0.0);
+
+    // ease back out, all the way back to the base fov
+    let remaining = fov_punch_timer.0 / PAUSE_TIME;
+    set_fov(
+        &mut q,
+        base_fov.0 - fov_punch(&last_hit, &reduce_motion) * remaining,
+    );
+}
+
+// counts down to the next throw while there's a pitch coming
+fn update_spawn_warning(
+    app_state: Res<State<AppState>>,
+    fixed_steps: Res<FixedSteps>,
+    pitch_config: Res<PitchConfig>,
+    next_pitch: Res<NextPitch>,
+    inning: Res<Inning>,
+    camera_intro: Res<CameraIntro>,
+    rule_effects: Res<RuleEffects>,
+    aim_assist: Res<AimAssist>,
+    mut spawn_warning: ResMut<SpawnWarning>,
+) {
+    let pitching = *app_state.current() != AppState::GameOver
+        && *app_state.current() != AppState::InningBreak
+        && *app_state.current() != AppState::PhotoMode
+        && *app_state.current() != AppState::Settings
+        && !camera_intro.active
+        && inning.pitches_left > 0;
+    match next_pitch
+        .origin
+        .and_then(|origin| pitch_config.origins.get(origin))
+    {
+        Some(origin) if pitching => {
+            let difficulty = next_pitch.pitch_type.map_or(0.0, |pitch_type| {
+                let flight_time =
+                    pitch_flight_time(origin, pitch_type, &rule_effects, &inning, &aim_assist);
+                let speed = (pitch_config.strike_point - origin.position).length() / flight_time;
+                pitch_difficulty(speed, pitch_type)
+            });
+            *spawn_warning = SpawnWarning {
+                position: origin.position,
+                timer: (PITCH_TIME - fixed_steps.accumulator("pitch")) as f32,
+                difficulty,
+                wild: next_pitch.wild,
+            };
+        }
+        _ => spawn_warning.timer = 0.0,
+    }
+}
+
+// fades out as the throw gets closer, green to red by difficulty
+fn update_spawn_warning_marker(
+    spawn_warning: Res<SpawnWarning>,
+    show_pitch_difficulty: Res<ShowPitchDifficulty>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q: Query<
+        (&mut Transform, &mut Visibility, &Handle<StandardMaterial>),
+        With<SpawnWarningMarker>,
+    >,
+) {
+    for (mut transform, mut visibility, material) in q.iter_mut() {
+        let showing = spawn_warning.timer > 0.0 && spawn_warning.timer < SPAWN_WARNING_TIME;
+        visibility.is_visible = showing;
+        if !showing {
+            continue;
+        }
+
+        transform.translation = spawn_warning.position;
+        // a slight wobble gives away a wild pitch, if you're watching for it
+        transform.scale = Vec3::splat(if spawn_warning.wild {
+            1.0 + (spawn_warning.timer * 40.0).sin() * WILD_PITCH_WOBBLE
+        } else {
+            1.0
+        });
+        if let Some(material) = materials.get_mut(material) {
+            let difficulty = spawn_warning.difficulty;
+            material.base_color = if show_pitch_difficulty.0 {
+                Color::rgb(
+                    difficulty.min(0.5) * 2.0,
+                    (1.0 - difficulty).min(0.5) * 2.0,
+                    0.1,
+                )
+            } else {
+                Color::rgb(1.0, 0.8, 0.2)
+            };
+            material
+                .base_color
+                .set_a(spawn_warning.timer / SPAWN_WARNING_TIME * 0.8);
+        }
+    }
+}
+
+// scale the custom mesh to a unit bounding sphere so Size still matches what you see
+fn load_custom_ball_mesh(
+    asset_server: Res<AssetServer>,
+    meshes: Res<Assets<Mesh>>,
+    mut ball_assets: ResMut<BallAssets>,
+) {
+    if ball_assets.custom_scale.is_some() {
+        return;
+    }
+    let custom_mesh = match &ball_assets.custom_mesh {
+        Some(custom_mesh) => custom_mesh,
+        None => return,
+    };
+
+    if asset_server.get_load_state(custom_mesh) == LoadState::Failed {
+        warn!("failed to load custom ball mesh, using the default sphere");
+        ball_assets.custom_mesh = None;
+        return;
+    }
+
+    let radius = match meshes
+        .get(custom_mesh)
+        .and_then(|mesh| mesh.attribute(Mesh::ATTRIBUTE_POSITION))
+    {
+        Some(VertexAttributeValues::Float32x3(positions)) => positions
+            .iter()
+            .map(|position| Vec3::from(*position).length())
+            .fold(0.0, f32::max),
+        _ => return,
+    };
+
+    if radius > 0.0 {
+        ball_assets.custom_scale = Some(1.0 / radius);
+    } else {
+        ball_assets.custom_mesh = None;
+    }
+}
+
+// same prediction the demo batter uses, soonest arrival wins
+fn pick_next_ball(
+    pitch_config: Res<PitchConfig>,
+    rule_effects: Res<RuleEffects>,
+    mut next_ball_highlight: ResMut<NextBallHighlight>,
+    q_balls: Query<(Entity, &Transform, &Velocity, &Curve, &BallKind, &Status)>,
+) {
+    if !next_ball_highlight.enabled {
+        next_ball_highlight.ball = None;
+        return;
+    }
+
+    next_ball_highlight.ball = q_balls
+        .iter()
+        .filter(|(.., status)| status.0 == BallStatus::Thrown)
+        .map(|(entity, transform, velocity, curve, kind, _)| {
+            let (_, eta) = predict_closest_pass(
+                transform.translation,
+                velocity.0,
+                curve.0,
+                GRAVITY * rule_effects.gravity_scale * kind.gravity_scale(),
+                pitch_config.strike_point,
+            );
+            (entity, eta)
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity);
+}
+
+fn update_next_ball_marker(
+    next_ball_highlight: Res<NextBallHighlight>,
+    q_balls: Query<(&Transform, &Size), Without<NextBallMarker>>,
+    mut q_marker: Query<(&mut Transform, &mut Visibility), With<NextBallMarker>>,
+) {
+    let ball = next_ball_highlight
+        .ball
+        .and_then(|ball| q_balls.get(ball).ok());
+
+    for (mut transform, mut visibility) in q_marker.iter_mut() {
+        visibility.is_visible = ball.is_some();
+        if let Some((ball_transform, size)) = ball {
+            transform.translation = ball_transform.translation;
+            transform.scale = Vec3::splat(size.0 * 1.6);
+        }
+    }
+}
+
+// where the curveball nearest the plate will be, dots spread evenly up to the plate
+fn update_curve_arc(
+    show_curve_arc: Res<ShowCurveArc>,
+    no_tells: Res<NoTells>,
+    pitch_config: Res<PitchConfig>,
+    rule_effects: Res<RuleEffects>,
+    q_balls: Query<(&Transform, &Velocity, &Curve, &BallKind, &Status), Without<CurveArcDot>>,
+    mut q_dots: Query<(&CurveArcDot, &mut Transform, &mut Visibility)>,
+) {
+    let ball = q_balls
+        .iter()
+        .filter(|(_, _, curve, _, status)| status.0 == BallStatus::Thrown && curve.0 != Vec3::ZERO)
+        .min_by(|(a, ..), (b, ..)| {
+            a.translation
+                .distance(pitch_config.strike_point)
+                .total_cmp(&b.translation.distance(pitch_config.strike_point))
+        });
+
+    let arc = match ball {
+        Some((transform, velocity, curve, kind, _)) if show_curve_arc.0 && !no_tells.0 => {
+            let gravity = GRAVITY * rule_effects.gravity_scale * kind.gravity_scale();
+            let (_, eta) = predict_closest_pass(
+                transform.translation,
+                velocity.0,
+                curve.0,
+                gravity,
+                pitch_config.strike_point,
+            );
+            Some((
+                transform.translation,
+                velocity.0,
+                curve.0,
+                gravity,
+                eta.min(CURVE_ARC_TIME),
+            ))
+        }
+        _ => None,
+    };
+
+    for (dot, mut transform, mut visibility) in q_dots.iter_mut() {
+        visibility.is_visible = arc.is_some();
+        if let Some((position, velocity, curve, gravity, horizon)) = arc {
+            // constant acceleration, same path the physics steps through
+            let t = horizon * (dot.0 + 1) as f32 / CURVE_ARC_DOTS as f32;
+            let acceleration = curve - vec3(0.0, gravity, 0.0);
+            transform.translation = position + velocity * t + 0.5 * acceleration * t * t;
+        }
+    }
+}
+
+// a training aid, so only shown along with aim assist
+fn update_strike_zone_marker(
+    show_strike_zone: Res<ShowStrikeZone>,
+    onboarding: Res<Onboarding>,
+    mut q: Query<&mut Visibility, With<StrikeZoneMarker>>,
+) {
+    for mut visibility in q.iter_mut() {
+        visibility.is_visible = show_strike_zone.0 && onboarding.aim_assist;
+    }
+}
+
+// where the bat tip would sweep swinging across at the current aim height, with aim assist
+fn update_swing_arc(
+    onboarding: Res<Onboarding>,
+    bat_aim: Res<BatAim>,
+    fielding_state: Res<State<FieldingState>>,
+    q_bat: Query<(&Transform, &GlobalTransform, &Parent, &BatOwner), With<Bat>>,
+    q_global: Query<&GlobalTransform>,
+    q_colliders: Query<(&GlobalTransform, &BatOwner), With<BatCollider>>,
+    mut q_dots: Query<(&SwingArcDot, &mut Transform, &mut Visibility), Without<Bat>>,
+) {
+    let bat = q_bat.iter().find(|(.., owner)| owner.0 == 0);
+    let player_transform = bat.and_then(|(_, _, parent, _)| q_global.get(**parent).ok());
+
+    let arc = match (bat, player_transform) {
+        (Some((bat_transform, bat_global, ..)), Some(player_transform))
+            if onboarding.aim_assist && *fielding_state.current() == FieldingState::Off =>
+        {
+            // the collider furthest out along the bat, in bat space
+            let to_bat = bat_global.compute_matrix().inverse();
+            q_colliders
+                .iter()
+                .filter(|(_, owner)| owner.0 == 0)
+                .map(|(global_transform, _)| {
+                    to_bat.transform_point3(global_transform.translation())
+                })
+                .max_by(|a, b| a.length().total_cmp(&b.length()))
+                .map(|tip| (*bat_transform, *player_transform, tip))
+        }
+        _ => None,
+    };
+
+    for (dot, mut transform, mut visibility) in q_dots.iter_mut() {
+        visibility.is_visible = arc.is_some();
+        if let Some((bat_transform, player_transform, tip)) = arc {
+            // same mapping update_bat_transform eases the bat toward
+            let aim_x = dot.0 as f32 / (SWING_ARC_DOTS - 1) as f32 - 0.5;
+            let (y, rotation) = bat_pose(vec2(aim_x, bat_aim.0.y));
+            let bat = Transform {
+                translation: vec3(bat_transform.translation.x, y, bat_transform.translation.z),
+                rotation,
+                scale: bat_transform.scale,
+            };
+            transform.translation = player_transform.mul_vec3(bat * tip);
+        }
+    }
+}
+
+fn update_wind_flag(
+    time: Res<Time>,
+    wind: Res<Wind>,
+    mut q: Query<&mut Transform, With<WindFlag>>,
+) {
+    let strength = (wind.0.length() / MAX_WIND).min(1.0);
+
+    // point the free end downwind, flap harder and hang less in strong wind
+    let heading = (-wind.0.z).atan2(wind.0.x);
+    let sway =
+        (time.seconds_since_startup() as f32 * (4.0 + 8.0 * strength)).sin() * 0.3 * strength;
+    let droop = (1.0 - strength) * 1.3;
+    for mut transform in q.iter_mut() {
+        transform.rotation = Quat::from_rotation_y(heading + sway) * Quat::from_rotation_z(-droop);
+    }
+}
+
+fn spawn_particle_events(mut particles: Particles, mut particle_events: EventReader<ParticleSpec>) {
+    for spec in particle_events.iter() {
+        spawn_particles(&mut particles, spec);
+    }
+}
+
+fn spawn_particles(particles: &mut Particles, spec: &ParticleSpec) {
+    let materials = &mut particles.materials;
+    let material = particles
+        .assets
+        .materials
+        .entry(spec.color.as_rgba_u32())
+        .or_insert_with(|| {
+            materials.add(StandardMaterial {
+                base_color: spec.color,
+                unlit: true,
+                ..default()
+            })
+        })
+        .clone();
+
+    for _ in 0..spec.count {
+        let jitter = vec3(
+            rand::random::<f32>() - 0.5,
+            rand::random::<f32>() - 0.5,
+            rand::random::<f32>() - 0.5,
+        ) * 2.0;
+        let particle = Particle {
+            velocity: (spec.direction + jitter * spec.spread) * spec.speed,
+            lifetime: spec.lifetime,
+            gravity: spec.gravity,
+        };
+        let transform =
+            Transform::from_translation(spec.position).with_scale(Vec3::splat(spec.size));
+
+        // reuse an expired particle if there is one
+        match particles.pool.0.pop() {
+            Some(entity) => {
+                particles.commands.entity(entity).insert_bundle((
+                    particle,
+                    transform,
+                    material.clone(),
+                    Visibility { is_visible: true },
+                ));
+            }
+            None => {
+                particles
+                    .commands
+                    .spawn_bundle(PbrBundle {
+                        mesh: particles.assets.mesh.clone(),
+                        material: material.clone(),
+                        transform,
+                        ..default()
+                    })
+                    .insert(particle);
+            }
+        }
+    }
+}
+
+fn update_particles(
+    time: Res<Time>,
+    mut pool: ResMut<ParticlePool>,
+    mut q: Query<(Entity, &mut Particle, &mut Transform, &mut Visibility)>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, mut visibility) in q.iter_mut() {
+        if particle.lifetime <= 0.0 {
+            continue;
+        }
+
+        particle.lifetime -= dt;
+        if particle.lifetime <= 0.0 {
+            visibility.is_visible = false;
+            pool.0.push(entity);
+            continue;
+        }
+
+        particle.velocity.y -= particle.gravity * dt;
+        transform.translation += particle.velocity * dt;
+    }
+}
+
+// a quick smear along the launch, older copies start fainter
+fn spawn_afterimages(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut hit_events: EventReader<HitEvent>,
+    q_balls: Query<(&Handle<Mesh>, &Transform)>,
+) {
+    for event in hit_events.iter() {
+        let (ball, exit_velocity, contact) = match event {
+            HitEvent::Ball {
+                ball,
+                power_hit: true,
+                exit_velocity,
+                contact,
+            } => (*ball, *exit_velocity, *contact),
+            _ => continue,
+        };
+        let (mesh, transform) = match q_balls.get(ball) {
+            Ok(ball) => ball,
+            Err(_) => continue,
+        };
+
+        for i in 1..=AFTERIMAGE_COUNT {
+            let alpha = AFTERIMAGE_ALPHA * i as f32 / AFTERIMAGE_COUNT as f32;
+            commands
+                .spawn_bundle(PbrBundle {
+                    mesh: mesh.clone(),
+                    material: materials.add(StandardMaterial {
+                        base_color: Color::rgba(1.0, 1.0, 1.0, alpha),
+                        unlit: true,
+                        alpha_mode: AlphaMode::Blend,
+                        ..default()
+                    }),
+                    transform: Transform::from_translation(
+                        contact + exit_velocity * AFTERIMAGE_STEP * i as f32,
+                    )
+                    .with_scale(transform.scale),
+                    ..default()
+                })
+                .insert(Lifetime(AFTERIMAGE_TIME))
+                .insert(Afterimage { alpha });
+        }
+    }
+}
+
+fn fade_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut q: Query<(
+        Entity,
+        &mut Lifetime,
+        &Afterimage,
+        &Handle<StandardMaterial>,
+    )>,
+) {
+    for (entity, mut lifetime, afterimage, material) in q.iter_mut() {
+        lifetime.0 -= time.delta_seconds();
+        if lifetime.0 <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        if let Some(material) = materials.get_mut(material) {
+            material
+                .base_color
+                .set_a(afterimage.alpha * lifetime.0 / AFTERIMAGE_TIME);
+        }
+    }
+}
+
+fn start_follow_hit_ball(
+    cinematic_hits: Res<CinematicHits>,
+    reduce_motion: Res<ReduceMotion>,
+    mut hit_events: EventReader<HitEvent>,
+    mut follow_hit_ball: ResMut<FollowHitBall>,
+) {
+    for event in hit_events.iter() {
+        if let HitEvent::Ball {
+            ball,
+            power_hit: true,
+            ..
+        } = event
+        {
+            // the swooping camera is the worst offender for motion sickness
+            if cinematic_hits.0 && !reduce_motion.0 {
+                follow_hit_ball.ball = Some(*ball);
+                follow_hit_ball.timer = FOLLOW_TIME;
+            }
+        }
+    }
+}
+
+fn follow_hit_ball(
+    time: Res<Time>,
+    mut follow_hit_ball: ResMut<FollowHitBall>,
+    mut q_camera: Query<(&mut Transform, &Parent), With<Camera3d>>,
+    q_global: Query<&GlobalTransform>,
+) {
+    if follow_hit_ball.timer <= 0.0 {
+        return;
+    }
+
+    follow_hit_ball.timer -= time.delta_seconds();
+
+    let (mut camera_transform, parent) = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    if follow_hit_ball.timer <= 0.0 {
+        *camera_transform = Transform::identity();
+        return;
+    }
+
+    match follow_hit_ball
+        .ball
+        .and_then(|ball| q_global.get(ball).ok())
+    {
+        Some(ball_transform) => follow_hit_ball.last_position = ball_transform.translation(),
+        None => {
+            // ball gone, head back to the default pose
+            follow_hit_ball.ball = None;
+            follow_hit_ball.timer = follow_hit_ball.timer.min(FOLLOW_BLEND_TIME);
+        }
+    }
+
+    let player_transform = match q_global.get(**parent) {
+        Ok(player_transform) => player_transform,
+        Err(_) => return,
+    };
+
+    // ease in, hold, ease out
+    let blend = ((FOLLOW_TIME - follow_hit_ball.timer) / FOLLOW_BLEND_TIME)
+        .min(follow_hit_ball.timer / FOLLOW_BLEND_TIME)
+        .clamp(0.0, 1.0);
+
+    let translation = vec3(0.0, 0.0, FOLLOW_DOLLY * blend);
+    let ball_local = player_transform
+        .compute_matrix()
+        .inverse()
+        .transform_point3(follow_hit_ball.last_position);
+    let look = Transform::from_translation(translation)
+        .looking_at(ball_local, Vec3::Y)
+        .rotation;
+
+    camera_transform.translation = translation;
+    camera_transform.rotation = Quat::IDENTITY.slerp(look, blend);
+}
+
+fn start_ball_cam(
+    reduce_motion: Res<ReduceMotion>,
+    mut ball_cam: ResMut<BallCam>,
+    mut follow_hit_ball: ResMut<FollowHitBall>,
+    mut time_scale: ResMut<TimeScale>,
+    q: Query<Entity, Added<HomeRun>>,
+) {
+    if reduce_motion.0 || ball_cam.timer > 0.0 {
+        return;
+    }
+
+    if let Some(ball) = q.iter().next() {
+        *ball_cam = BallCam {
+            ball: Some(ball),
+            timer: BALL_CAM_TIME,
+        };
+        // ball cam takes over from the power hit dolly
+        follow_hit_ball.timer = 0.0;
+        time_scale.slow_motion = BALL_CAM_TIME_SCALE;
+    }
+}
+
+// chase the ball until it's out of the park, the timer runs out or any input skips
+fn ball_cam(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut ball_cam: ResMut<BallCam>,
+    mut time_scale: ResMut<TimeScale>,
+    mut q_camera: Query<(&mut Transform, &Parent), With<Camera3d>>,
+    q_global: Query<&GlobalTransform>,
+    q_balls: Query<(&GlobalTransform, &Velocity)>,
+) {
+    if ball_cam.timer <= 0.0 {
+        return;
+    }
+
+    let (mut camera_transform, parent) = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    ball_cam.timer -= time.delta_seconds();
+
+    let skipped = keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some();
+    let ball = ball_cam.ball.and_then(|ball| q_balls.get(ball).ok());
+    let (ball_transform, velocity) = match ball {
+        Some((ball_transform, velocity))
+            if !skipped
+                && ball_cam.timer > 0.0
+                && ball_transform.translation().length() < BALL_CAM_MAX_DISTANCE =>
+        {
+            (ball_transform, velocity)
+        }
+        _ => {
+            *ball_cam = BallCam::default();
+            time_scale.slow_motion = 1.0;
+            *camera_transform = Transform::identity();
+            return;
+        }
+    };
+    let player_transform = match q_global.get(**parent) {
+        Ok(player_transform) => player_transform,
+        Err(_) => return,
+    };
+
+    // trail behind and a little above the ball
+    let ball_pos = ball_transform.translation();
+    let behind = -velocity.0.normalize_or_zero() * BALL_CAM_DISTANCE + Vec3::Y * 0.4;
+    let world = Transform::from_translation(ball_pos + behind).looking_at(ball_pos, Vec3::Y);
+
+    // the camera hangs off the player, so go through its space
+    *camera_transform = Transform::from_matrix(
+        player_transform.compute_matrix().inverse() * world.compute_matrix(),
+    );
+}
+
+// take the camera out of the player where it stands, and hide the hud for a clean shot
+fn enter_photo_mode(
+    mut commands: Commands,
+    mut photo_camera: ResMut<PhotoCamera>,
+    mut q_camera: Query<(Entity, &mut Transform, &GlobalTransform, &Parent), With<Camera3d>>,
+) {
+    let (camera, mut camera_transform, global_transform, parent) = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    let world_transform = global_transform.compute_transform();
+    let (yaw, pitch, _) = world_transform.rotation.to_euler(EulerRot::YXZ);
+    *photo_camera = PhotoCamera {
+        parent: Some(**parent),
+        transform: *camera_transform,
+        yaw,
+        pitch,
+    };
+
+    *camera_transform = world_transform;
+    commands.entity(**parent).remove_children(&[camera]);
+}
+
+// drag to look, wasd to move, q and e for down and up
+fn fly_photo_camera(
+    sim_clock: Res<SimClock>,
+    photo_input: Res<PhotoInput>,
+    mut photo_camera: ResMut<PhotoCamera>,
+    mut q_camera: Query<&mut Transform, With<Camera3d>>,
+) {
+    let mut camera_transform = match q_camera.get_single_mut() {
+        Ok(camera_transform) => camera_transform,
+        Err(_) => return,
+    };
+
+    photo_camera.yaw -= photo_input.look.x * PHOTO_LOOK_SPEED;
+    photo_camera.pitch =
+        (photo_camera.pitch - photo_input.look.y * PHOTO_LOOK_SPEED).clamp(-1.5, 1.5);
+    camera_transform.rotation =
+        Quat::from_euler(EulerRot::YXZ, photo_camera.yaw, photo_camera.pitch, 0.0);
+
+    let movement = camera_transform.rotation * photo_input.movement;
+    camera_transform.translation += movement * PHOTO_MOVE_SPEED * sim_clock.real_delta;
+}
+
+// back into the player, exactly where it was
+fn exit_photo_mode(
+    mut commands: Commands,
+    mut photo_camera: ResMut<PhotoCamera>,
+    mut q_camera: Query<(Entity, &mut Transform), With<Camera3d>>,
+) {
+    let (camera, mut camera_transform) = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    *camera_transform = photo_camera.transform;
+    if let Some(parent) = photo_camera.parent.take() {
+        commands.entity(parent).push_children(&[camera]);
+    }
+}
+
+// on the wall clock, the game holds still until it's done
+fn play_camera_intro(
+    sim_clock: Res<SimClock>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut camera_intro: ResMut<CameraIntro>,
+    mut q_camera: Query<(&mut Transform, &Parent), With<Camera3d>>,
+    q_global: Query<&GlobalTransform>,
+) {
+    if !camera_intro.active {
+        return;
+    }
+
+    let (mut camera_transform, parent) = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    camera_intro.elapsed += sim_clock.real_delta;
+
+    // any input skips straight to play
+    let skipped = keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some();
+    let end = camera_intro.keyframes.last().map_or(0.0, |last| last.time);
+    if skipped || camera_intro.elapsed >= end {
+        camera_intro.active = false;
+        *camera_transform = Transform::identity();
+        return;
+    }
+
+    let player_transform = match q_global.get(**parent) {
+        Ok(player_transform) => player_transform,
+        Err(_) => return,
+    };
+
+    // find the segment we're in and ease across it
+    let next = camera_intro
+        .keyframes
+        .iter()
+        .position(|keyframe| keyframe.time > camera_intro.elapsed)
+        .unwrap_or(camera_intro.keyframes.len() - 1)
+        .max(1);
+    let from = &camera_intro.keyframes[next - 1];
+    let to = &camera_intro.keyframes[next];
+    let t = ((camera_intro.elapsed - from.time) / (to.time - from.time)).clamp(0.0, 1.0);
+    let t = t * t * (3.0 - 2.0 * t);
+
+    let from_pose = Transform::from_translation(from.position).looking_at(from.look_at, Vec3::Y);
+    let to_pose = Transform::from_translation(to.position).looking_at(to.look_at, Vec3::Y);
+    let world = Transform {
+        translation: from_pose.translation.lerp(to_pose.translation, t),
+        rotation: from_pose.rotation.slerp(to_pose.rotation, t),
+        ..default()
+    };
+
+    // the camera hangs off the player, so go through its space
+    *camera_transform = Transform::from_matrix(
+        player_transform.compute_matrix().inverse() * world.compute_matrix(),
+    );
+}
+
+// gently turn the camera toward the nearest incoming pitch
+fn trackball(
+    time: Res<Time>,
+    camera_tracking: Res<CameraTracking>,
+    reduce_motion: Res<ReduceMotion>,
+    follow_hit_ball: Res<FollowHitBall>,
+    ball_cam: Res<BallCam>,
+    camera_intro: Res<CameraIntro>,
+    mut q_camera: Query<(&mut Transform, &Parent), With<Camera3d>>,
+    q_global: Query<&GlobalTransform>,
+    q_balls: Query<(&GlobalTransform, &Status)>,
+) {
+    // the hit camera, ball cam and intro own the rotation while they run
+    if follow_hit_ball.timer > 0.0 || ball_cam.timer > 0.0 || camera_intro.active {
+        return;
+    }
+
+    let (mut camera_transform, parent) = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+    let player_transform = match q_global.get(**parent) {
+        Ok(player_transform) => player_transform,
+        Err(_) => return,
+    };
+
+    let player_pos = player_transform.translation();
+    let nearest = q_balls
+        .iter()
+        .filter(|(_, status)| status.0 == BallStatus::Thrown)
+        .map(|(ball_transform, _)| ball_transform.translation())
+        .min_by(|a, b| a.distance(player_pos).total_cmp(&b.distance(player_pos)));
+
+    let target = match nearest {
+        Some(ball_pos) => {
+            let ball_local = player_transform
+                .compute_matrix()
+                .inverse()
+                .transform_point3(ball_pos);
+            let look = Transform::identity()
+                .looking_at(ball_local, Vec3::Y)
+                .rotation;
+            Quat::IDENTITY.slerp(look, camera_tracking.intensity * reduce_motion.scale())
+        }
+        None => Quat::IDENTITY,
+    };
+
+    let n = (time.delta_seconds() * 5.0).min(1.0);
+    camera_transform.rotation = camera_transform.rotation.slerp(target, n);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn particles_expire_after_their_lifetime() {
+        let mut app = App::new();
+        app.insert_resource(Time::default())
+            .insert_resource(ParticlePool::default())
+            .add_system(update_particles);
+        for _ in 0..3 {
+            app.world.spawn().insert_bundle((
+                Particle {
+                    velocity: Vec3::Y,
+                    lifetime: 0.5,
+                    gravity: 4.0,
+                },
+                Transform::default(),
+                Visibility::default(),
+            ));
+        }
+
+        // a second of frames at 60 fps, well past the lifetime
+        let start = Instant::now();
+        app.world.resource_mut::<Time>().update_with_instant(start);
+        for frame in 1..=60 {
+            app.world
+                .resource_mut::<Time>()
+                .update_with_instant(start + Duration::from_secs_f32(frame as f32 / 60.0));
+            app.update();
+        }
+
+        // expired particles are hidden and handed back to the pool for reuse
+        let mut q = app.world.query::<(&Particle, &Visibility)>();
+        let live = q
+            .iter(&app.world)
+            .filter(|(particle, visibility)| particle.lifetime > 0.0 || visibility.is_visible)
+            .count();
+        assert_eq!(live, 0);
+        assert_eq!(app.world.resource::<ParticlePool>().0.len(), 3);
+    }
+}
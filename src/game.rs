@@ -0,0 +1,1195 @@
+use crate::{
+    components::*,
+    config::{load_config, save_config},
+    physics::{bat_pose, hit_score, predict_closest_pass, predict_landing},
+};
+use bevy::{
+    ecs::schedule::ShouldRun,
+    math::{vec2, vec3},
+    prelude::*,
+    utils::Duration,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// rules, scoring and flow of a game, plus the resources every other part reads
+pub(crate) struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state(AppState::InGame)
+            .add_state(FieldingState::Off)
+            .insert_resource(Fielding::default())
+            .add_event::<TargetHitEvent>()
+            .add_event::<HitEvent>()
+            .add_event::<ParticleSpec>()
+            .add_event::<NewGameEvent>()
+            .insert_resource(InningBreakTimer(0.0))
+            .insert_resource(Inning::default())
+            .insert_resource(InningScores::default())
+            .insert_resource(InputRecording::default())
+            .insert_resource(ReplayInput::default())
+            .insert_resource(SimClock::default())
+            .insert_resource(FixedSteps::default())
+            .insert_resource(BatAim::default())
+            .insert_resource(GamepadAim::default())
+            .insert_resource(AiDriver::default())
+            .insert_resource(NoTells(false))
+            .insert_resource(load_config::<Onboarding>("ld51_onboarding").unwrap_or_default())
+            .insert_resource(load_config::<RunHistory>("ld51_run_history").unwrap_or_default())
+            .insert_resource(AimAssist(1.0))
+            .insert_resource(RecentSwings::default())
+            .insert_resource(CinematicHits(false))
+            .insert_resource(Wind(Vec3::ZERO))
+            .insert_resource(Score(0))
+            .insert_resource(Combo::default())
+            .insert_resource(Stats::default())
+            .insert_resource(LastPitch::default())
+            .insert_resource(Rules { outs_allowed: 3 })
+            .insert_resource(Outs(0))
+            .insert_resource(load_config("ld51_match_length").unwrap_or(MatchLength(None)))
+            .insert_resource(PitchCount::default())
+            .insert_resource(GameTime(0.0))
+            .insert_resource(LastHit::default())
+            .insert_resource(SessionBests::default())
+            .insert_resource(TargetSequence::default())
+            .insert_resource(BatShrinkMilestone(0))
+            .insert_resource(Announcement {
+                text: String::new(),
+                timer: 0.0,
+            })
+            .insert_resource(HitHeatmap::default())
+            .insert_resource(load_rule_schedule())
+            .insert_resource(RuleEffects::default())
+            .add_system_set(
+                // new wind every x seconds
+                SystemSet::on_update(AppState::InGame)
+                    .with_run_criteria(fixed_step("wind", WIND_SHIFT_TIME))
+                    .with_system(shift_wind),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(drive_ai_aim)
+                    .with_system(detect_misses)
+                    .with_system(update_fielders)
+                    .with_system(update_player_fielder)
+                    .with_system(check_game_over)
+                    .with_system(check_inning_over)
+                    .with_system(tick_game_time)
+                    .with_system(run_rule_schedule)
+                    .with_system(detect_target_hits)
+                    .with_system(award_ricochet_bonus)
+                    // a completed sequence's announcement wins
+                    .with_system(update_target_sequence.after(award_ricochet_bonus))
+                    .with_system(apply_hazard_penalty)
+                    .with_system(update_aim_assist)
+                    .with_system(decay_combo),
+            )
+            // a new game starts on a frame boundary, so its first frame is the same live and replayed
+            .add_system_to_stage(CoreStage::First, start_new_game)
+            .add_system_to_stage(CoreStage::First, drive_sim_clock.after(start_new_game))
+            .add_system(record_bat_aim.after(drive_ai_aim))
+            .add_system(start_fielding)
+            .add_system_set(
+                SystemSet::on_enter(FieldingState::Aiming).with_system(spawn_player_fielder),
+            )
+            .add_system_set(
+                SystemSet::on_exit(FieldingState::Aiming).with_system(despawn_player_fielder),
+            )
+            .add_system(announce_hit_power)
+            // any state, a power hit's event is gone by the time hit pause ends
+            .add_system(read_hit_radar)
+            .add_system(record_hit_heatmap)
+            .add_system(detect_home_runs)
+            .add_system(update_combo)
+            .add_system(update_stats)
+            .add_system(score_strike_zone)
+            .add_system_set(
+                SystemSet::on_enter(AppState::InningBreak).with_system(start_inning_break),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::InningBreak).with_system(update_inning_break),
+            );
+    }
+}
+
+// more help while the player is new and missing, none once they're not
+fn update_aim_assist(
+    time: Res<Time>,
+    onboarding: Res<Onboarding>,
+    mut aim_assist: ResMut<AimAssist>,
+    mut recent_swings: ResMut<RecentSwings>,
+    q_balls: Query<&Status, (Changed<Status>, Without<Hazard>)>,
+) {
+    for status in q_balls.iter() {
+        if status.0 == BallStatus::Thrown {
+            continue;
+        }
+        recent_swings.0.push_back(status.0 == BallStatus::Hit);
+        if recent_swings.0.len() > AIM_ASSIST_WINDOW {
+            recent_swings.0.pop_front();
+        }
+    }
+
+    let target = if onboarding.aim_assist {
+        let hits = recent_swings.0.iter().filter(|hit| **hit).count();
+        let hit_rate = if recent_swings.0.is_empty() {
+            0.5
+        } else {
+            hits as f32 / recent_swings.0.len() as f32
+        };
+        let experience = (onboarding.runs as f32 / AIM_ASSIST_RUNS as f32).min(1.0);
+        1.0 + MAX_AIM_ASSIST * (1.0 - experience) * (1.0 - hit_rate)
+    } else {
+        1.0
+    };
+
+    let n = (time.delta_seconds() * 0.5).min(1.0);
+    aim_assist.0 += (target - aim_assist.0) * n;
+}
+
+// meet the nearest pitch: wind up to one side, then swing through the predicted contact
+fn drive_ai_aim(
+    pitch_config: Res<PitchConfig>,
+    rule_effects: Res<RuleEffects>,
+    mut ai_driver: ResMut<AiDriver>,
+    mut ai_rng: ResMut<AiRng>,
+    mut bat_aim: ResMut<BatAim>,
+    q_bat: Query<(&Parent, &BatOwner), With<Bat>>,
+    q_visual: Query<(&Transform, &BatOwner), With<BatVisual>>,
+    q_global: Query<&GlobalTransform>,
+    q_balls: Query<(Entity, &Transform, &Velocity, &Curve, &BallKind, &Status), Without<Hazard>>,
+) {
+    if !ai_driver.enabled {
+        return;
+    }
+
+    // the demo batter plays the mouse player's bat
+    let player_transform = match q_bat.iter().find(|(_, owner)| owner.0 == 0) {
+        Some((parent, _)) => match q_global.get(**parent) {
+            Ok(player_transform) => player_transform,
+            Err(_) => return,
+        },
+        None => return,
+    };
+    let visual_transform = match q_visual.iter().find(|(_, owner)| owner.0 == 0) {
+        Some((visual_transform, _)) => visual_transform,
+        None => return,
+    };
+
+    let nearest = q_balls
+        .iter()
+        .filter(|(.., status)| status.0 == BallStatus::Thrown)
+        .map(|(entity, transform, velocity, curve, kind, _)| {
+            let (contact, eta) = predict_closest_pass(
+                transform.translation,
+                velocity.0,
+                curve.0,
+                GRAVITY * rule_effects.gravity_scale * kind.gravity_scale(),
+                pitch_config.strike_point,
+            );
+            (entity, contact, eta)
+        })
+        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+
+    let (ball, contact, eta) = match nearest {
+        Some(nearest) => nearest,
+        None => {
+            ai_driver.ball = None;
+            return;
+        }
+    };
+
+    // new pitch, sometimes misjudge it like a person would
+    if ai_driver.ball != Some(ball) {
+        ai_driver.ball = Some(ball);
+        ai_driver.swing_lead = if ai_rng.0.gen::<f32>() < AI_MISTIME_CHANCE {
+            AI_SWING_LEAD + ai_rng.0.gen_range(-0.12..0.12)
+        } else {
+            AI_SWING_LEAD
+        };
+    }
+
+    // search for the aim that puts the bat closest to the contact point
+    let contact_local = player_transform
+        .compute_matrix()
+        .inverse()
+        .transform_point3(contact);
+    let handle = visual_transform.mul_vec3(vec3(0.0, -0.4, 0.0));
+    let tip = visual_transform.mul_vec3(vec3(0.0, 0.5, 0.0));
+    let mut best = (Vec2::ZERO, f32::MAX);
+    for i in 0..=20 {
+        for j in 0..=20 {
+            let aim = vec2(i as f32 / 20.0 - 0.5, j as f32 / 20.0 - 0.5);
+            let (y, rotation) = bat_pose(aim);
+            let bat = Transform::from_xyz(0.0, y, -1.0).with_rotation(rotation);
+            let distance = distance_to_segment(contact_local, bat * handle, bat * tip);
+            if distance < best.1 {
+                best = (aim, distance);
+            }
+        }
+    }
+
+    let aim = best.0;
+    bat_aim.0 = if eta > ai_driver.swing_lead {
+        vec2((aim.x - AI_BACKSWING).max(-0.5), aim.y)
+    } else {
+        aim
+    };
+}
+
+fn distance_to_segment(point: Vec3, a: Vec3, b: Vec3) -> f32 {
+    let ab = b - a;
+    let t = ((point - a).dot(ab) / ab.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+fn record_hit_heatmap(
+    mut hit_events: EventReader<HitEvent>,
+    mut hit_heatmap: ResMut<HitHeatmap>,
+    q_visual: Query<&GlobalTransform, With<BatVisual>>,
+) {
+    for event in hit_events.iter() {
+        if let HitEvent::Ball { contact, .. } = event {
+            // in co-op, whichever bat made the contact
+            let visual_transform = match q_visual.iter().min_by(|a, b| {
+                a.translation()
+                    .distance(*contact)
+                    .total_cmp(&b.translation().distance(*contact))
+            }) {
+                Some(visual_transform) => visual_transform,
+                None => return,
+            };
+            let to_bat = visual_transform.compute_matrix().inverse();
+
+            // bat runs along local y, a bit past the capsule ends
+            let local = to_bat.transform_point3(*contact);
+            let along = ((local.y + 0.7) / 1.4).clamp(0.0, 0.999);
+            let across = ((local.x + 0.3) / 0.6).clamp(0.0, 0.999);
+            let row = (along * HEATMAP_ROWS as f32) as usize;
+            let col = (across * HEATMAP_COLS as f32) as usize;
+            hit_heatmap.counts[row][col] += 1;
+        }
+    }
+}
+
+fn detect_misses(
+    mut outs: ResMut<Outs>,
+    mut score: ResMut<Score>,
+    mut last_pitch: ResMut<LastPitch>,
+    mut announcement: ResMut<Announcement>,
+    mut q_balls: Query<(
+        &Transform,
+        &mut Status,
+        &PitchSpeed,
+        Option<&Hazard>,
+        Option<&WildPitch>,
+        Option<&Accelerating>,
+    )>,
+) {
+    for (transform, mut status, pitch_speed, hazard, wild_pitch, accelerating) in q_balls.iter_mut()
+    {
+        if status.0 == BallStatus::Thrown
+            && transform.translation.x + transform.translation.z > MISS_LINE
+        {
+            status.0 = BallStatus::Missed;
+            *last_pitch = LastPitch {
+                speed: pitch_speed.0,
+                exit_speed: None,
+                timer: RADAR_TIME,
+            };
+
+            // letting a bomb by is the right call
+            if hazard.is_some() {
+                score.0 += HAZARD_PASS_SCORE;
+            } else if accelerating.is_some() {
+                outs.0 += PRESSURE_BALL_OUTS;
+                announcement.text = "Too slow! Pressure ball".to_string();
+                announcement.timer = 1.5;
+            } else if wild_pitch.is_none() {
+                outs.0 += 1;
+            }
+        }
+    }
+}
+
+fn apply_hazard_penalty(
+    mut hit_events: EventReader<HitEvent>,
+    mut outs: ResMut<Outs>,
+    mut announcement: ResMut<Announcement>,
+) {
+    for event in hit_events.iter() {
+        if let HitEvent::Hazard { .. } = event {
+            outs.0 += 1;
+            announcement.text = "Bomb! That's an out".to_string();
+            announcement.timer = 1.5;
+        }
+    }
+}
+
+fn check_game_over(
+    mut app_state: ResMut<State<AppState>>,
+    rules: Res<Rules>,
+    outs: Res<Outs>,
+    match_length: Res<MatchLength>,
+    pitch_count: Res<PitchCount>,
+    fielding_state: Res<State<FieldingState>>,
+    q_balls: Query<&Status>,
+) {
+    // a match ends after its last pitch is hit, fielded or missed
+    let match_over = match_length.finished(&pitch_count)
+        && *fielding_state.current() == FieldingState::Off
+        && q_balls.iter().all(|status| status.0 != BallStatus::Thrown);
+
+    if outs.0 >= rules.outs_allowed || match_over {
+        app_state.overwrite_set(AppState::GameOver).unwrap();
+    }
+}
+
+// once every pitch of the inning has been dealt with, take a break
+fn check_inning_over(
+    mut app_state: ResMut<State<AppState>>,
+    inning: Res<Inning>,
+    rules: Res<Rules>,
+    outs: Res<Outs>,
+    match_length: Res<MatchLength>,
+    pitch_count: Res<PitchCount>,
+    q_balls: Query<&Status>,
+) {
+    if inning.pitches_left > 0
+        || outs.0 >= rules.outs_allowed
+        || match_length.finished(&pitch_count)
+    {
+        return;
+    }
+
+    if q_balls.iter().all(|status| status.0 != BallStatus::Thrown) {
+        app_state.overwrite_set(AppState::InningBreak).unwrap();
+    }
+}
+
+fn start_inning_break(
+    score: Res<Score>,
+    mut inning_scores: ResMut<InningScores>,
+    mut inning_break_timer: ResMut<InningBreakTimer>,
+) {
+    let earlier: u32 = inning_scores.0.iter().sum();
+    inning_scores.0.push(score.0.saturating_sub(earlier));
+    inning_break_timer.0 = INNING_BREAK_TIME;
+}
+
+fn update_inning_break(
+    time: Res<Time>,
+    mut inning_break_timer: ResMut<InningBreakTimer>,
+    mut inning: ResMut<Inning>,
+    mut app_state: ResMut<State<AppState>>,
+) {
+    inning_break_timer.0 -= time.delta_seconds();
+
+    if inning_break_timer.0 < 0.0 {
+        *inning = Inning {
+            number: inning.number + 1,
+            pitches_left: PITCHES_PER_INNING,
+        };
+        app_state.set(AppState::InGame).unwrap();
+    }
+}
+
+fn update_fielders(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut outs: ResMut<Outs>,
+    mut q_fielders: Query<(&Fielder, &mut Transform), Without<Status>>,
+    q_balls: Query<(Entity, &Transform, &Status)>,
+) {
+    let mut caught = Vec::new();
+
+    for (fielder, mut transform) in q_fielders.iter_mut() {
+        // nearest ball still in the park
+        let target = q_balls
+            .iter()
+            .filter(|(entity, ball_transform, status)| {
+                status.0 == BallStatus::Hit
+                    && !caught.iter().any(|(caught, _)| caught == entity)
+                    && vec2(ball_transform.translation.x, ball_transform.translation.z).length()
+                        < FENCE_DISTANCE
+            })
+            .min_by(|(_, a, _), (_, b, _)| {
+                a.translation
+                    .distance(transform.translation)
+                    .total_cmp(&b.translation.distance(transform.translation))
+            });
+
+        // run along the ground toward the ball, or back home
+        let goal = match target {
+            Some((_, ball_transform, _)) => ball_transform.translation,
+            None => fielder.home,
+        };
+        let offset = vec3(
+            goal.x - transform.translation.x,
+            0.0,
+            goal.z - transform.translation.z,
+        );
+        let step = fielder.speed * time.delta_seconds() * time_scale.simulation();
+        if offset.length() > step {
+            transform.translation += offset.normalize() * step;
+        } else {
+            transform.translation += offset;
+        }
+
+        // caught out
+        if let Some((entity, ball_transform, _)) = target {
+            if offset.length() < CATCH_DISTANCE && ball_transform.translation.y < CATCH_HEIGHT {
+                caught.push((entity, ball_transform.scale));
+            }
+        }
+    }
+
+    // out of play right away, the ball shrinks away in the fielder's glove
+    for (entity, scale) in caught {
+        commands
+            .entity(entity)
+            .remove::<Status>()
+            .remove::<SpawnAnim>()
+            .insert(DespawnAnim { timer: 0.0, scale });
+        outs.0 += 1;
+    }
+}
+
+// a high fly that stays in the park hands the controls to a fielder until it comes down
+fn start_fielding(
+    rule_effects: Res<RuleEffects>,
+    mut fielding: ResMut<Fielding>,
+    mut fielding_state: ResMut<State<FieldingState>>,
+    mut hit_events: EventReader<HitEvent>,
+    q_kinds: Query<&BallKind>,
+) {
+    if *fielding_state.current() != FieldingState::Off {
+        return;
+    }
+
+    for event in hit_events.iter() {
+        if let HitEvent::Ball {
+            ball,
+            exit_velocity,
+            contact,
+            ..
+        } = *event
+        {
+            let kind_gravity_scale = q_kinds.get(ball).map_or(1.0, |kind| kind.gravity_scale());
+            let (landing, hang_time) = predict_landing(
+                contact,
+                exit_velocity,
+                GRAVITY * rule_effects.gravity_scale * kind_gravity_scale,
+            );
+            if hang_time > FIELDING_MIN_HANG && vec2(landing.x, landing.z).length() < FENCE_DISTANCE
+            {
+                *fielding = Fielding {
+                    ball: Some(ball),
+                    landing,
+                };
+                fielding_state.set(FieldingState::Aiming).unwrap();
+                break;
+            }
+        }
+    }
+}
+
+fn spawn_player_fielder(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    fielding: Res<Fielding>,
+) {
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Capsule {
+                radius: 0.15,
+                depth: 0.5,
+                ..default()
+            })),
+            material: materials.add(Color::AZURE.into()),
+            transform: Transform::from_translation(PLAYER_FIELDER_HOME),
+            ..default()
+        })
+        .insert(PlayerFielder);
+
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Icosphere {
+                radius: CATCH_DISTANCE,
+                subdivisions: 2,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.0, 0.5, 1.0, 0.5),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            }),
+            transform: Transform::from_translation(fielding.landing + Vec3::Y * 0.01)
+                .with_scale(vec3(1.0, 0.02, 1.0)),
+            ..default()
+        })
+        .insert(LandingMarker);
+}
+
+fn despawn_player_fielder(
+    mut commands: Commands,
+    q: Query<Entity, Or<(With<PlayerFielder>, With<LandingMarker>)>>,
+) {
+    for entity in q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// the aim picks a spot around the fielder's home to run to, catching the ball first pays a bonus
+fn update_player_fielder(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    bat_aim: Res<BatAim>,
+    mut score: ResMut<Score>,
+    mut announcement: ResMut<Announcement>,
+    mut fielding: ResMut<Fielding>,
+    mut fielding_state: ResMut<State<FieldingState>>,
+    mut q_fielder: Query<&mut Transform, (With<PlayerFielder>, Without<Status>)>,
+    q_balls: Query<(&Transform, &Status, &Size)>,
+) {
+    if *fielding_state.current() != FieldingState::Aiming {
+        return;
+    }
+
+    // caught by a fielder, landed or gone over the fence
+    let ball_transform = match fielding.ball.and_then(|ball| q_balls.get(ball).ok()) {
+        Some((ball_transform, status, size))
+            if status.0 == BallStatus::Hit
+                && ball_transform.translation.y > size.0 + GROUND_CONTACT_MARGIN
+                && vec2(ball_transform.translation.x, ball_transform.translation.z).length()
+                    < FENCE_DISTANCE =>
+        {
+            ball_transform
+        }
+        _ => {
+            fielding.ball = None;
+            fielding_state.overwrite_set(FieldingState::Off).unwrap();
+            return;
+        }
+    };
+
+    // screen right and up, seen from the plate
+    let right = vec3(1.0, 0.0, -1.0).normalize();
+    let forward = vec3(-1.0, 0.0, -1.0).normalize();
+    let goal = PLAYER_FIELDER_HOME
+        + (right * bat_aim.0.x + forward * bat_aim.0.y) * 2.0 * PLAYER_FIELDER_REACH;
+
+    for mut transform in q_fielder.iter_mut() {
+        let offset = goal - transform.translation;
+        let step = PLAYER_FIELDER_SPEED * time.delta_seconds() * time_scale.simulation();
+        if offset.length() > step {
+            transform.translation += offset.normalize() * step;
+        } else {
+            transform.translation += offset;
+        }
+
+        let reach = vec3(
+            ball_transform.translation.x - transform.translation.x,
+            0.0,
+            ball_transform.translation.z - transform.translation.z,
+        );
+        if reach.length() < CATCH_DISTANCE && ball_transform.translation.y < CATCH_HEIGHT {
+            if let Some(ball) = fielding.ball.take() {
+                commands
+                    .entity(ball)
+                    .remove::<Status>()
+                    .remove::<SpawnAnim>()
+                    .insert(DespawnAnim {
+                        timer: 0.0,
+                        scale: ball_transform.scale,
+                    });
+            }
+            score.0 += FIELDING_BONUS;
+            announcement.text = format!("Nice catch! +{}", FIELDING_BONUS);
+            announcement.timer = 1.5;
+            fielding_state.overwrite_set(FieldingState::Off).unwrap();
+            return;
+        }
+    }
+}
+
+fn start_new_game(
+    mut commands: Commands,
+    mut new_game_events: EventReader<NewGameEvent>,
+    mut app_state: ResMut<State<AppState>>,
+    mut fielding_state: ResMut<State<FieldingState>>,
+    (mut score, mut outs, mut game_time, mut bat_shrink_milestone, mut session_bests): (
+        ResMut<Score>,
+        ResMut<Outs>,
+        ResMut<GameTime>,
+        ResMut<BatShrinkMilestone>,
+        ResMut<SessionBests>,
+    ),
+    (mut rng, mut game_seed, mut input_recording, mut replay_input, mut ai_driver): (
+        ResMut<GameRng>,
+        ResMut<GameSeed>,
+        ResMut<InputRecording>,
+        ResMut<ReplayInput>,
+        ResMut<AiDriver>,
+    ),
+    (mut inning, mut inning_scores, mut next_pitch, mut pitch_count): (
+        ResMut<Inning>,
+        ResMut<InningScores>,
+        ResMut<NextPitch>,
+        ResMut<PitchCount>,
+    ),
+    (mut wind, mut sim_clock, mut ai_rng): (ResMut<Wind>, ResMut<SimClock>, ResMut<AiRng>),
+    q_balls: Query<Entity, With<Status>>,
+) {
+    let event = match new_game_events.iter().last() {
+        Some(event) => event,
+        None => return,
+    };
+
+    for entity in q_balls.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    score.0 = 0;
+    outs.0 = 0;
+    game_time.0 = 0.0;
+    *session_bests = SessionBests::default();
+    bat_shrink_milestone.0 = 0;
+    *inning = Inning::default();
+    inning_scores.0.clear();
+    pitch_count.0 = 0;
+
+    // a new seed each run keeps every run shareable on its own
+    game_seed.0 = event.seed;
+    rng.0 = StdRng::seed_from_u64(event.seed);
+    *ai_rng = AiRng::new(event.seed);
+    *next_pitch = NextPitch::default();
+    wind.0 = Vec3::ZERO;
+    input_recording.0.clear();
+    replay_input.0 = event.replay.clone();
+    sim_clock.run_started = true;
+    if replay_input.0.is_some() {
+        ai_driver.enabled = false;
+    }
+
+    if *fielding_state.current() != FieldingState::Off {
+        fielding_state.overwrite_set(FieldingState::Off).unwrap();
+    }
+
+    // replace rather than set, so a new game also leaves photo mode
+    if *app_state.current() != AppState::InGame {
+        app_state.overwrite_replace(AppState::InGame).unwrap();
+    }
+}
+
+// every frame of a run goes into the recording with the exact length the game steps it by,
+// a replay steps by the recorded lengths instead, however long its frames really take
+fn drive_sim_clock(
+    mut time: ResMut<Time>,
+    mut sim_clock: ResMut<SimClock>,
+    mut fixed_steps: ResMut<FixedSteps>,
+    mut input_recording: ResMut<InputRecording>,
+    app_state: Res<State<AppState>>,
+    replay_input: Res<ReplayInput>,
+    camera_intro: Res<CameraIntro>,
+) {
+    let now = match time.last_update() {
+        Some(now) => now,
+        None => return,
+    };
+    let real_delta = sim_clock
+        .last_real
+        .map_or(Duration::ZERO, |last_real| now - last_real);
+    sim_clock.last_real = Some(now);
+    sim_clock.real_delta = real_delta.as_secs_f32();
+
+    // menus, photo mode and the intro hold the game still
+    let holding = camera_intro.active
+        || matches!(
+            app_state.current(),
+            AppState::PhotoMode | AppState::Settings
+        );
+    let in_run = sim_clock.run_started
+        || (!holding
+            && matches!(
+                app_state.current(),
+                AppState::InGame | AppState::HitPause | AppState::InningBreak
+            ));
+    sim_clock.run_started = false;
+
+    let delta = if in_run {
+        let index = input_recording.0.len();
+        let frame = match replay_input.0.as_ref().and_then(|frames| frames.get(index)) {
+            Some(frame) => *frame,
+            None => InputFrame {
+                dt: (real_delta.as_micros() / SHARE_DT_MICROS as u128).min(u16::MAX as u128) as u16,
+                aim: input_recording.0.last().map_or([0, 0], |frame| frame.aim),
+            },
+        };
+        // fixed steps start in phase with the run
+        if index == 0 {
+            fixed_steps.0.clear();
+        }
+        input_recording.0.push(frame);
+        sim_clock.frame = Some(index);
+        Duration::from_micros(frame.dt as u64 * SHARE_DT_MICROS)
+    } else {
+        sim_clock.frame = None;
+        if holding {
+            Duration::ZERO
+        } else {
+            real_delta
+        }
+    };
+
+    let sim_time = sim_clock.time.get_or_insert_with(|| time.clone());
+    let last_update = sim_time.last_update().unwrap_or(now);
+    sim_time.update_with_instant(last_update + delta);
+    *time = sim_time.clone();
+}
+
+// like FixedTimestep, but only counting frames of the run and starting over with it,
+// so a replay steps on the same frames the recording did
+pub(crate) fn fixed_step(
+    name: &'static str,
+    step: f64,
+) -> impl FnMut(Local<bool>, Res<Time>, Res<SimClock>, ResMut<FixedSteps>) -> ShouldRun {
+    move |mut looping, time, sim_clock, mut fixed_steps| {
+        let accumulator = fixed_steps.0.entry(name).or_default();
+        if !*looping && sim_clock.frame.is_some() {
+            *accumulator += time.delta_seconds_f64();
+        }
+
+        *looping = *accumulator >= step;
+        if *looping {
+            *accumulator -= step;
+            ShouldRun::YesAndCheckAgain
+        } else {
+            ShouldRun::No
+        }
+    }
+}
+
+// live aim goes into the recording quantized and a replay reads it back out,
+// either way the bat follows the recorded value
+pub(crate) fn record_bat_aim(
+    sim_clock: Res<SimClock>,
+    replay_input: Res<ReplayInput>,
+    mut input_recording: ResMut<InputRecording>,
+    mut bat_aim: ResMut<BatAim>,
+) {
+    let frame = match sim_clock
+        .frame
+        .and_then(|index| input_recording.0.get_mut(index))
+    {
+        Some(frame) => frame,
+        None => return,
+    };
+
+    // a replay past its end holds the last aim
+    if replay_input.0.is_none() {
+        let quantize = |aim: f32| (aim * 254.0).round().clamp(-127.0, 127.0) as i8;
+        frame.aim = [quantize(bat_aim.0.x), quantize(bat_aim.0.y)];
+    }
+    bat_aim.0 = vec2(frame.aim[0] as f32 / 254.0, frame.aim[1] as f32 / 254.0);
+}
+
+// version, seed, then run-length encoded frames and a checksum
+pub(crate) fn encode_share_code(share_code: &ShareCode) -> String {
+    let mut bytes = vec![SHARE_CODE_VERSION];
+    bytes.extend_from_slice(&share_code.seed.to_le_bytes());
+
+    let mut frames = share_code.frames.iter().peekable();
+    while let Some(frame) = frames.next() {
+        let mut run = 1u8;
+        while run < u8::MAX && frames.peek() == Some(&frame) {
+            frames.next();
+            run += 1;
+        }
+        let [dt_low, dt_high] = frame.dt.to_le_bytes();
+        bytes.extend_from_slice(&[run, dt_low, dt_high, frame.aim[0] as u8, frame.aim[1] as u8]);
+    }
+
+    let checksum = bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    bytes.push(checksum);
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+pub(crate) fn decode_share_code(text: &str) -> Result<ShareCode, String> {
+    let bytes =
+        base64::decode_config(text.trim(), base64::URL_SAFE_NO_PAD).map_err(|e| e.to_string())?;
+
+    let (checksum, bytes) = bytes.split_last().ok_or("empty code")?;
+    if bytes.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != *checksum {
+        return Err("checksum mismatch".to_string());
+    }
+    if bytes.len() < 9 {
+        return Err("wrong length".to_string());
+    }
+    if bytes[0] != SHARE_CODE_VERSION {
+        return Err(format!("unknown version {}", bytes[0]));
+    }
+    if (bytes.len() - 9) % 5 != 0 {
+        return Err("wrong length".to_string());
+    }
+
+    let mut seed = [0; 8];
+    seed.copy_from_slice(&bytes[1..9]);
+    let mut frames = Vec::new();
+    for run in bytes[9..].chunks(5) {
+        let frame = InputFrame {
+            dt: u16::from_le_bytes([run[1], run[2]]),
+            aim: [run[3] as i8, run[4] as i8],
+        };
+        frames.extend(std::iter::repeat(frame).take(run[0] as usize));
+    }
+
+    Ok(ShareCode {
+        seed: u64::from_le_bytes(seed),
+        frames,
+    })
+}
+
+fn shift_wind(mut wind: ResMut<Wind>, mut rng: ResMut<GameRng>) {
+    let angle = rng.0.gen::<f32>() * std::f32::consts::TAU;
+    let strength = rng.0.gen::<f32>() * MAX_WIND;
+    wind.0 = vec3(angle.cos(), 0.0, angle.sin()) * strength;
+}
+
+fn tick_game_time(time: Res<Time>, mut game_time: ResMut<GameTime>) {
+    game_time.0 += time.delta_seconds();
+}
+
+// write the default schedule out on first run so there's something to edit
+fn load_rule_schedule() -> RuleSchedule {
+    load_config("ld51_rules").unwrap_or_else(|| {
+        let rule_schedule = RuleSchedule::default();
+        save_config("ld51_rules", &rule_schedule);
+        rule_schedule
+    })
+}
+
+fn run_rule_schedule(
+    game_time: Res<GameTime>,
+    mut rule_schedule: ResMut<RuleSchedule>,
+    mut rule_effects: ResMut<RuleEffects>,
+    mut announcement: ResMut<Announcement>,
+) {
+    let rule_schedule = &mut *rule_schedule;
+    rule_schedule
+        .active
+        .resize(rule_schedule.rules.len(), false);
+
+    let mut effects = RuleEffects::default();
+    for (rule, was_active) in rule_schedule
+        .rules
+        .iter()
+        .zip(rule_schedule.active.iter_mut())
+    {
+        // first trigger is after one full interval, not at the start
+        let active = rule.every > 0.0
+            && game_time.0 >= rule.every
+            && game_time.0 % rule.every < rule.duration;
+
+        if active && !*was_active {
+            announcement.text = format!("{}!", rule.effect.name());
+            announcement.timer = 2.0;
+        }
+        *was_active = active;
+
+        if !active {
+            continue;
+        }
+        match rule.effect {
+            GameEffect::GravityScale(scale) => effects.gravity_scale *= scale,
+            GameEffect::PitchSpeed(speed) => effects.pitch_speed *= speed,
+            GameEffect::BatScale(scale) => effects.bat_scale *= scale,
+            GameEffect::MultiBall(extra) => effects.extra_balls += extra,
+        }
+    }
+    *rule_effects = effects;
+}
+
+fn detect_target_hits(
+    mut commands: Commands,
+    mut target_hit_events: EventWriter<TargetHitEvent>,
+    q_targets: Query<(&Target, &Transform)>,
+    q_balls: Query<(Entity, &Transform, &Size, &Status, &BounceCount), Without<ScoredTarget>>,
+) {
+    for (entity, ball_transform, size, status, bounce_count) in q_balls.iter() {
+        if status.0 != BallStatus::Hit {
+            continue;
+        }
+
+        for (target, target_transform) in q_targets.iter() {
+            // small margin, physics pushes bouncing balls out to the surface
+            if ball_transform
+                .translation
+                .distance(target_transform.translation)
+                < TARGET_RADIUS + size.0 + 0.01
+            {
+                commands.entity(entity).insert(ScoredTarget);
+                target_hit_events.send(TargetHitEvent {
+                    target: target.0,
+                    bounces: bounce_count.0,
+                });
+                break;
+            }
+        }
+    }
+}
+
+// still in the air past the fence, the crowd and the ball cam both go off it
+fn detect_home_runs(
+    mut commands: Commands,
+    q_balls: Query<(Entity, &Transform, &Size, &Status), Without<HomeRun>>,
+) {
+    for (entity, transform, size, status) in q_balls.iter() {
+        let distance = vec2(transform.translation.x, transform.translation.z).length();
+        if status.0 == BallStatus::Hit
+            && distance > FENCE_DISTANCE
+            && transform.translation.y > size.0
+        {
+            commands.entity(entity).insert(HomeRun);
+        }
+    }
+}
+
+// trick shots, doubling per bounce, whether or not a sequence is running
+fn award_ricochet_bonus(
+    mut target_hit_events: EventReader<TargetHitEvent>,
+    mut score: ResMut<Score>,
+    mut announcement: ResMut<Announcement>,
+) {
+    for event in target_hit_events.iter() {
+        if event.bounces > 0 {
+            let bonus = RICOCHET_SCORE << (event.bounces - 1).min(4);
+            score.0 += bonus;
+            announcement.text = format!("Ricochet x{}! +{}", event.bounces, bonus);
+            announcement.timer = 1.5;
+        }
+    }
+}
+
+fn update_target_sequence(
+    time: Res<Time>,
+    mut target_hit_events: EventReader<TargetHitEvent>,
+    mut target_sequence: ResMut<TargetSequence>,
+    mut score: ResMut<Score>,
+    mut announcement: ResMut<Announcement>,
+    q_targets: Query<&Target>,
+) {
+    if !target_sequence.enabled {
+        target_hit_events.clear();
+        return;
+    }
+
+    target_sequence.timer += time.delta_seconds();
+
+    for event in target_hit_events.iter() {
+        if event.target != target_sequence.index {
+            // wrong order, start over
+            target_sequence.index = 0;
+            target_sequence.streak = 0;
+            target_sequence.timer = 0.0;
+            continue;
+        }
+
+        target_sequence.index += 1;
+        target_sequence.streak += 1;
+
+        if target_sequence.index == q_targets.iter().count() {
+            let bonus = ((TARGET_PAR_TIME - target_sequence.timer).max(0.0) * 10.0) as u32;
+            score.0 += bonus;
+            announcement.text = format!("Sequence complete! +{}", bonus);
+            announcement.timer = 2.0;
+
+            target_sequence.index = 0;
+            target_sequence.timer = 0.0;
+        }
+    }
+}
+
+// extra score on top of the plain hit score for contact toward the zone's corners
+fn score_strike_zone(
+    strike_zone: Res<StrikeZone>,
+    mut score: ResMut<Score>,
+    mut hit_events: EventReader<HitEvent>,
+    q_balls: Query<(&Size, &BallKind), Without<Hazard>>,
+) {
+    for event in hit_events.iter() {
+        if let HitEvent::Ball { ball, contact, .. } = event {
+            // the corner bonus is on top of what physics paid for the hit, scaled the same way
+            let (size, kind) = match q_balls.get(*ball) {
+                Ok(ball) => ball,
+                Err(_) => continue,
+            };
+            let multiplier = strike_zone.multiplier(*contact);
+            score.0 +=
+                (hit_score(size.0) as f32 * kind.score_scale() * (multiplier - 1.0)).round() as u32;
+        }
+    }
+}
+
+fn update_combo(
+    outs: Res<Outs>,
+    mut combo: ResMut<Combo>,
+    mut hit_events: EventReader<HitEvent>,
+    mut new_game_events: EventReader<NewGameEvent>,
+) {
+    for event in hit_events.iter() {
+        *combo = match event {
+            HitEvent::Ball {
+                power_hit: true, ..
+            } => Combo {
+                count: combo.count + 1,
+                timer: COMBO_WINDOW,
+            },
+            _ => Combo::default(),
+        };
+    }
+    if outs.is_changed() || new_game_events.iter().next().is_some() {
+        *combo = Combo::default();
+    }
+}
+
+// real seconds, so the window is the same at any frame rate, and held during hit pause
+fn decay_combo(time: Res<Time>, time_scale: Res<TimeScale>, mut combo: ResMut<Combo>) {
+    if combo.count == 0 {
+        return;
+    }
+
+    tick_combo(&mut combo, time.delta_seconds() * time_scale.debug);
+}
+
+fn tick_combo(combo: &mut Combo, dt: f32) {
+    combo.timer -= dt;
+    if combo.timer <= 0.0 {
+        *combo = Combo::default();
+    }
+}
+
+fn update_stats(
+    sandbox: Res<Sandbox>,
+    mut stats: ResMut<Stats>,
+    mut hit_events: EventReader<HitEvent>,
+    mut new_game_events: EventReader<NewGameEvent>,
+) {
+    if new_game_events.iter().next().is_some() {
+        *stats = Stats::default();
+    }
+    stats.sandbox |= sandbox.0;
+    for event in hit_events.iter() {
+        if let HitEvent::Ball {
+            power_hit,
+            exit_velocity,
+            ..
+        } = event
+        {
+            stats.hits += 1;
+            stats.power_hits += *power_hit as u32;
+            stats.exit_speed_total += exit_velocity.length();
+        }
+    }
+}
+
+// power hits and near misses, so players learn how hard to swing
+fn announce_hit_power(
+    physics_config: Res<PhysicsConfig>,
+    last_hit: Res<LastHit>,
+    mut announcement: ResMut<Announcement>,
+    mut hit_events: EventReader<HitEvent>,
+) {
+    for event in hit_events.iter() {
+        if let HitEvent::Ball { power_hit, .. } = event {
+            announcement.text = if *power_hit {
+                "Power hit!".to_string()
+            } else if last_hit.power > physics_config.power_hit_threshold * NEAR_POWER_HIT {
+                "So close!".to_string()
+            } else {
+                continue;
+            };
+            announcement.timer = 1.0;
+        }
+    }
+}
+
+fn read_hit_radar(
+    mut hit_events: EventReader<HitEvent>,
+    last_hit: Res<LastHit>,
+    mut last_pitch: ResMut<LastPitch>,
+    mut session_bests: ResMut<SessionBests>,
+    (sandbox, stats): (Res<Sandbox>, Res<Stats>),
+    q_pitch_speed: Query<&PitchSpeed>,
+) {
+    for event in hit_events.iter() {
+        if let HitEvent::Ball {
+            ball,
+            exit_velocity,
+            ..
+        } = event
+        {
+            *last_pitch = LastPitch {
+                speed: q_pitch_speed.get(*ball).map(|speed| speed.0).unwrap_or(0.0),
+                exit_speed: Some(exit_velocity.length()),
+                timer: RADAR_TIME,
+            };
+
+            // sandbox hits don't count
+            if last_hit.exit_speed > session_bests.exit_speed && !sandbox.0 && !stats.sandbox {
+                *session_bests = SessionBests {
+                    exit_speed: last_hit.exit_speed,
+                    launch_angle: last_hit.launch_angle,
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_code_round_trips() {
+        let frame = |dt, aim| InputFrame { dt, aim };
+        let mut frames = vec![frame(0, [0, 0]), frame(166, [-127, 127])];
+        // longer than one run can hold
+        frames.extend(std::iter::repeat(frame(167, [12, -40])).take(300));
+        frames.push(frame(u16::MAX, [127, -127]));
+        let share_code = ShareCode {
+            seed: 0x0123_4567_89ab_cdef,
+            frames,
+        };
+
+        let decoded = decode_share_code(&encode_share_code(&share_code));
+        assert_eq!(decoded, Ok(share_code));
+    }
+
+    #[test]
+    fn combo_window_runs_out_the_same_at_any_frame_rate() {
+        for dt in [0.5, 1.5] {
+            let mut one_frame = Combo {
+                count: 3,
+                timer: 1.0,
+            };
+            tick_combo(&mut one_frame, dt);
+
+            let mut ten_frames = Combo {
+                count: 3,
+                timer: 1.0,
+            };
+            for _ in 0..10 {
+                if ten_frames.count > 0 {
+                    tick_combo(&mut ten_frames, dt / 10.0);
+                }
+            }
+
+            assert_eq!(one_frame.count, ten_frames.count);
+            assert!((one_frame.timer - ten_frames.timer).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{components::*, game::decode_share_code, GameApi, GameplayPlugin};
+use bevy::{math::vec2, prelude::*};
+use rand::{rngs::StdRng, SeedableRng};
+use std::time::{Duration, Instant};
+
+// the game without a window, stepped by hand with a fixed clock
+struct Sim {
+    app: App,
+    clock: Instant,
+}
+
+impl Sim {
+    fn new(seed: u64) -> Self {
+        let mut app = App::new();
+        // one thread, so systems without an order between them run the same way every time
+        app.insert_resource(bevy::core::DefaultTaskPoolOptions::with_num_threads(1))
+            .add_plugin(bevy::core::CorePlugin::default())
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(bevy::asset::AssetPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .init_resource::<Time>()
+            .insert_resource(GameRng(StdRng::seed_from_u64(seed)))
+            .insert_resource(AiRng::new(seed))
+            .insert_resource(GameSeed(seed))
+            .insert_resource(CameraIntro {
+                active: false,
+                ..default()
+            });
+        app.add_plugin(GameplayPlugin);
+
+        let clock = Instant::now();
+        app.world.resource_mut::<Time>().update_with_instant(clock);
+
+        Self { app, clock }
+    }
+
+    // advance the game by dt seconds regardless of how long it really takes
+    fn step(&mut self, dt: f32) {
+        self.clock += Duration::from_secs_f32(dt);
+        self.app
+            .world
+            .resource_mut::<Time>()
+            .update_with_instant(self.clock);
+        self.app.update();
+    }
+
+    fn set_aim(&mut self, aim: Vec2) {
+        self.app.world.resource_mut::<BatAim>().0 = aim;
+    }
+
+    // drive the clock and the bat from a share code's frames instead of step and set_aim
+    fn set_replay(&mut self, frames: Option<Vec<InputFrame>>) {
+        self.app.world.resource_mut::<ReplayInput>().0 = frames;
+    }
+
+    // let the demo batter swing instead of set_aim
+    fn set_ai(&mut self, enabled: bool) {
+        self.app.world.resource_mut::<AiDriver>().enabled = enabled;
+    }
+
+    fn score(&self) -> u32 {
+        self.app.world.current_score()
+    }
+
+    fn outs(&self) -> u32 {
+        self.app.world.outs()
+    }
+
+    fn game_over(&self) -> bool {
+        self.app.world.is_game_over()
+    }
+
+    // position, velocity and status of every ball in play
+    fn balls(&mut self) -> Vec<(Vec3, Vec3, BallStatus)> {
+        self.app
+            .world
+            .query::<(&Transform, &Velocity, &Status)>()
+            .iter(&self.app.world)
+            .map(|(transform, velocity, status)| (transform.translation, velocity.0, status.0))
+            .collect()
+    }
+}
+
+// back and forth across the zone, never quite in time with the pitches
+fn sweep_aim(elapsed: f32) -> Vec2 {
+    vec2((elapsed * 3.0).sin() * 0.4, (elapsed * 1.3).cos() * 0.3)
+}
+
+// plays until the game ends, then prints the result
+// the bat sweeps across the zone, with --ai the demo batter plays, --replay <code> watches a run
+pub fn run_headless() {
+    let seed = std::env::args()
+        .skip_while(|arg| arg != "--seed")
+        .nth(1)
+        .and_then(|seed| seed.parse().ok())
+        .unwrap_or(0);
+    let ai = std::env::args().any(|arg| arg == "--ai");
+    let replay = std::env::args()
+        .skip_while(|arg| arg != "--replay")
+        .nth(1)
+        .map(|code| decode_share_code(&code));
+
+    let (seed, replay) = match replay {
+        Some(Ok(share_code)) => (share_code.seed, Some(share_code.frames)),
+        Some(Err(e)) => {
+            eprintln!("bad share code: {}", e);
+            return;
+        }
+        None => (seed, None),
+    };
+    let replaying = replay.is_some();
+
+    let mut sim = Sim::new(seed);
+    sim.set_ai(ai);
+    sim.set_replay(replay);
+
+    let dt = 1.0 / 60.0;
+    let mut elapsed = 0.0;
+    while !sim.game_over() && elapsed < 600.0 {
+        if !ai && !replaying {
+            sim.set_aim(sweep_aim(elapsed));
+        }
+        sim.step(dt);
+        elapsed += dt;
+    }
+
+    println!(
+        "seed {}  score {}  outs {}  time {:.1}s  balls left {}",
+        seed,
+        sim.score(),
+        sim.outs(),
+        elapsed,
+        sim.balls().len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // after a change that's meant to play differently, regenerate with
+    //   LD51_BLESS=1 cargo test golden_run
+    // and commit the rewritten file along with the change
+    // a missing file is written on the first run
+    static GOLDEN_RUN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden/headless_seed_7.txt");
+
+    // twenty seconds of the headless sweep on seed 7, the score and every ball in play
+    fn golden_run() -> String {
+        let mut sim = Sim::new(7);
+        let dt = 1.0 / 60.0;
+        for step in 0..1200 {
+            sim.set_aim(sweep_aim(step as f32 * dt));
+            sim.step(dt);
+        }
+
+        let mut balls = sim
+            .balls()
+            .into_iter()
+            .map(|(position, velocity, status)| {
+                format!(
+                    "{:?} at {:.3} {:.3} {:.3} moving {:.3} {:.3} {:.3}",
+                    status, position.x, position.y, position.z, velocity.x, velocity.y, velocity.z
+                )
+            })
+            .collect::<Vec<_>>();
+        balls.sort();
+        format!(
+            "score {}\nouts {}\n{}\n",
+            sim.score(),
+            sim.outs(),
+            balls.join("\n")
+        )
+    }
+
+    #[test]
+    fn golden_run_plays_out_the_same() {
+        let run = golden_run();
+        let golden = std::fs::read_to_string(GOLDEN_RUN).ok();
+        if golden.is_none() || std::env::var_os("LD51_BLESS").is_some() {
+            let path = std::path::Path::new(GOLDEN_RUN);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, &run).unwrap();
+            return;
+        }
+        assert_eq!(
+            golden.as_deref(),
+            Some(run.as_str()),
+            "headless run changed, see the comment on GOLDEN_RUN if that's intended"
+        );
+    }
+}
@@ -0,0 +1,894 @@
+use crate::{
+    components::*,
+    config::{append_csv, load_config, save_config, unix_time},
+    game::{decode_share_code, encode_share_code},
+};
+use bevy::{
+    app::AppExit,
+    input::{mouse::MouseMotion, InputSystem},
+    math::{vec2, vec3},
+    prelude::*,
+};
+
+// keyboard and mouse, everything the player presses
+pub(crate) struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(LastMousePosition(vec2(0.0, 0.0)))
+            .insert_resource(load_config("ld51_cursor_mode").unwrap_or(CursorMode::Free))
+            .insert_resource(load_config::<MouseAim>("ld51_mouse_aim").unwrap_or_default())
+            .insert_resource(load_config::<KeyBindings>("ld51_key_bindings").unwrap_or_default())
+            .insert_resource(Rebinding::default())
+            .insert_resource(ShareCodeInput::default())
+            .insert_resource(PhotoInput::default())
+            .insert_resource(SettingsMenu::default())
+            .insert_resource(load_config("ld51_restart_delay").unwrap_or(RestartDelay(1.0)))
+            .insert_resource(GameOverTimer::default())
+            .insert_resource(ConfirmQuit::default())
+            .add_system_to_stage(CoreStage::PreUpdate, rebind_keys.after(InputSystem))
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                photo_mode_input
+                    .after(InputSystem)
+                    .after(rebind_keys)
+                    .before(type_share_code),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, type_share_code.after(InputSystem))
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                settings_input.after(rebind_keys).after(type_share_code),
+            )
+            .add_system_to_stage(CoreStage::PreUpdate, read_mouse_aim)
+            .add_system(apply_cursor_mode)
+            .add_system_to_stage(CoreStage::PreUpdate, read_gamepad_aim)
+            .add_system(toggle_co_op)
+            .add_system(toggle_curve_arc)
+            .add_system(toggle_strike_zone)
+            .add_system(toggle_pitch_difficulty)
+            .add_system(toggle_sandbox)
+            .add_system_set(
+                // waiting for restart
+                SystemSet::on_update(AppState::GameOver)
+                    .with_system(restart)
+                    .with_system(quit),
+            )
+            .add_system_set(
+                SystemSet::on_enter(AppState::GameOver)
+                    .with_system(count_run)
+                    .with_system(record_run)
+                    .with_system(write_stats_csv)
+                    .with_system(start_game_over_timer),
+            )
+            .add_system(toggle_bat_mode)
+            .add_system(adjust_bat_colliders)
+            .add_system(toggle_vsync)
+            .add_system(cycle_camera_preset)
+            .add_system(cycle_frame_cap)
+            .add_system(toggle_next_ball_highlight)
+            .add_system(toggle_target_sequence)
+            .add_system(toggle_gravity_wells)
+            .add_system(toggle_backboard)
+            .add_system(hold_study_freeze)
+            .add_system(toggle_no_tells)
+            .add_system(toggle_cinematic_hits)
+            .add_system(toggle_hit_stop)
+            .add_system(toggle_reduce_motion)
+            .add_system(toggle_ai)
+            .add_system(share_run)
+            .add_system(toggle_aim_assist)
+            .add_system(build_pitch_deck)
+            .add_system(cycle_bat_weight);
+    }
+}
+
+// cycle presets, or bump a pitch's weight to start a custom deck
+fn build_pitch_deck(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut pitch_deck: ResMut<PitchDeck>,
+) {
+    if keys.just_pressed(key_bindings.cycle_pitch_deck) {
+        let presets = PitchDeck::presets();
+        let next = presets
+            .iter()
+            .position(|preset| preset.name == pitch_deck.name)
+            .map_or(0, |i| (i + 1) % presets.len());
+        *pitch_deck = PitchDeck::presets().remove(next);
+        return;
+    }
+
+    let bumped = [
+        (key_bindings.more_fastballs, PitchType::Fastball),
+        (key_bindings.more_curveballs, PitchType::Curveball),
+        (key_bindings.more_changeups, PitchType::Changeup),
+    ]
+    .into_iter()
+    .find(|(key, _)| keys.just_pressed(*key));
+
+    if let Some((_, bumped)) = bumped {
+        pitch_deck.name = "Custom";
+        for (pitch_type, weight) in pitch_deck.pitches.iter_mut() {
+            // wraps back to zero so a pitch can be left out
+            if *pitch_type == bumped {
+                *weight = (*weight + 1) % 4;
+            }
+        }
+    }
+}
+
+fn toggle_no_tells(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut no_tells: ResMut<NoTells>,
+) {
+    if keys.just_pressed(key_bindings.toggle_no_tells) {
+        no_tells.0 = !no_tells.0;
+    }
+}
+
+fn toggle_curve_arc(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    no_tells: Res<NoTells>,
+    mut show_curve_arc: ResMut<ShowCurveArc>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.toggle_curve_arc) {
+        show_curve_arc.0 = !show_curve_arc.0;
+        announcement.text = match (show_curve_arc.0, no_tells.0) {
+            (true, true) => "Curveball path hidden while pitch colors are off",
+            (true, false) => "Curveball path on",
+            (false, _) => "Curveball path off",
+        }
+        .to_string();
+        announcement.timer = 1.5;
+    }
+}
+
+fn toggle_strike_zone(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut show_strike_zone: ResMut<ShowStrikeZone>,
+) {
+    if keys.just_pressed(key_bindings.toggle_strike_zone) {
+        show_strike_zone.0 = !show_strike_zone.0;
+    }
+}
+
+fn toggle_pitch_difficulty(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut show_pitch_difficulty: ResMut<ShowPitchDifficulty>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.toggle_pitch_difficulty) {
+        show_pitch_difficulty.0 = !show_pitch_difficulty.0;
+        save_config("ld51_pitch_difficulty", &*show_pitch_difficulty);
+        announcement.text = if show_pitch_difficulty.0 {
+            "Pitch difficulty colors on"
+        } else {
+            "Pitch difficulty colors off"
+        }
+        .to_string();
+        announcement.timer = 1.5;
+    }
+}
+
+// hidden on purpose, ctrl shift s
+fn toggle_sandbox(
+    keys: Res<Input<KeyCode>>,
+    mut sandbox: ResMut<Sandbox>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.pressed(KeyCode::LControl)
+        && keys.pressed(KeyCode::LShift)
+        && keys.just_pressed(KeyCode::S)
+    {
+        sandbox.0 = !sandbox.0;
+        announcement.text = if sandbox.0 {
+            "Sandbox on, this run won't count"
+        } else {
+            "Sandbox off"
+        }
+        .to_string();
+        announcement.timer = 1.5;
+    }
+}
+
+// left stick of the first gamepad aims the co-op bat
+fn read_gamepad_aim(
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut gamepad_aim: ResMut<GamepadAim>,
+) {
+    let gamepad = match gamepads.iter().next().copied() {
+        Some(gamepad) => gamepad,
+        None => return,
+    };
+    let axis = |axis_type| {
+        axes.get(GamepadAxis::new(gamepad, axis_type))
+            .unwrap_or(0.0)
+    };
+
+    gamepad_aim.0 = vec2(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    ) * 0.5;
+}
+
+fn toggle_co_op(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut co_op: ResMut<CoOp>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.toggle_co_op) {
+        co_op.0 = !co_op.0;
+        announcement.text = if co_op.0 {
+            "Co-op on, gamepad joins"
+        } else {
+            "Co-op off"
+        }
+        .to_string();
+        announcement.timer = 1.0;
+    }
+}
+
+fn apply_cursor_mode(cursor_mode: Res<CursorMode>, mut windows: ResMut<Windows>) {
+    if !cursor_mode.is_changed() {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_cursor_lock_mode(cursor_mode.grabbed());
+        window.set_cursor_visibility(*cursor_mode != CursorMode::Hidden);
+    }
+}
+
+fn read_mouse_aim(
+    windows: Res<Windows>,
+    ai_driver: Res<AiDriver>,
+    replay_input: Res<ReplayInput>,
+    cursor_mode: Res<CursorMode>,
+    mouse_aim: Res<MouseAim>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut last_mouse_position: ResMut<LastMousePosition>,
+    mut bat_aim: ResMut<BatAim>,
+) {
+    if ai_driver.enabled || replay_input.0.is_some() {
+        return;
+    }
+
+    // nudge the aim by the motion, screen up is up
+    if mouse_aim.relative {
+        let delta: Vec2 = mouse_motion.iter().map(|motion| motion.delta).sum();
+        bat_aim.0 = (bat_aim.0
+            + vec2(delta.x, -delta.y) * RELATIVE_AIM_RATE * mouse_aim.sensitivity)
+            .clamp(Vec2::splat(-0.5), Vec2::splat(0.5));
+        return;
+    }
+
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let cursor_position = if cursor_mode.grabbed() {
+        // some platforms lock a grabbed cursor in place, so follow the raw motion instead
+        let delta: Vec2 = mouse_motion.iter().map(|motion| motion.delta).sum();
+        last_mouse_position.0 = (last_mouse_position.0 + vec2(delta.x, -delta.y))
+            .clamp(Vec2::ZERO, vec2(window.width(), window.height()));
+        last_mouse_position.0
+    } else {
+        match window.cursor_position() {
+            Some(position) => {
+                last_mouse_position.0 = position;
+                position
+            }
+            None => last_mouse_position.0,
+        }
+    };
+
+    // virtual joystick
+    bat_aim.0 = vec2(
+        cursor_position.x / window.width() - 0.5,
+        cursor_position.y / window.height() - 0.5,
+    );
+}
+
+fn count_run(mut onboarding: ResMut<Onboarding>) {
+    onboarding.runs += 1;
+    save_config("ld51_onboarding", &*onboarding);
+}
+
+// sandbox runs aren't counted here either
+fn record_run(
+    score: Res<Score>,
+    stats: Res<Stats>,
+    game_seed: Res<GameSeed>,
+    mut run_history: ResMut<RunHistory>,
+) {
+    if stats.sandbox {
+        return;
+    }
+
+    run_history.0.push_back(PastRun {
+        score: score.0,
+        timestamp: unix_time(),
+        seed: game_seed.0,
+    });
+    while run_history.0.len() > RUN_HISTORY_LENGTH {
+        run_history.0.pop_front();
+    }
+    save_config("ld51_run_history", &*run_history);
+}
+
+fn write_stats_csv(score: Res<Score>, stats: Res<Stats>, game_seed: Res<GameSeed>) {
+    append_csv(
+        "ld51_stats",
+        "score,hits,power_hits,avg_exit_mph,seed,sandbox",
+        &format!(
+            "{},{},{},{:.1},{},{}",
+            score.0,
+            stats.hits,
+            stats.power_hits,
+            stats.average_exit_speed() * RADAR_MPH,
+            game_seed.0,
+            stats.sandbox
+        ),
+    );
+}
+
+fn toggle_aim_assist(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut onboarding: ResMut<Onboarding>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.toggle_aim_assist) {
+        onboarding.aim_assist = !onboarding.aim_assist;
+        save_config("ld51_onboarding", &*onboarding);
+
+        announcement.text = if onboarding.aim_assist {
+            "Aim assist on"
+        } else {
+            "Aim assist off"
+        }
+        .to_string();
+        announcement.timer = 1.0;
+    }
+}
+
+fn toggle_ai(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut ai_driver: ResMut<AiDriver>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.toggle_ai) {
+        ai_driver.enabled = !ai_driver.enabled;
+        announcement.text = if ai_driver.enabled {
+            "Demo batter"
+        } else {
+            "Your turn"
+        }
+        .to_string();
+        announcement.timer = 1.0;
+    }
+}
+
+fn toggle_next_ball_highlight(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut next_ball_highlight: ResMut<NextBallHighlight>,
+) {
+    if keys.just_pressed(key_bindings.toggle_next_ball_highlight) {
+        next_ball_highlight.enabled = !next_ball_highlight.enabled;
+    }
+}
+
+fn cycle_bat_weight(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut bat_weight: ResMut<BatWeight>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if !keys.just_pressed(key_bindings.cycle_bat_weight) {
+        return;
+    }
+
+    *bat_weight = bat_weight.next();
+    announcement.text = match *bat_weight {
+        BatWeight::Light => "Light bat",
+        BatWeight::Medium => "Medium bat",
+        BatWeight::Heavy => "Heavy bat",
+    }
+    .to_string();
+    announcement.timer = 1.0;
+}
+
+fn toggle_bat_mode(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut bat_config: ResMut<BatConfig>,
+) {
+    if keys.just_pressed(key_bindings.toggle_bat_mode) {
+        bat_config.floppy = !bat_config.floppy;
+    }
+}
+
+fn adjust_bat_colliders(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut bat_collider_count: ResMut<BatColliderCount>,
+    mut announcement: ResMut<Announcement>,
+) {
+    let count = if keys.just_pressed(key_bindings.fewer_bat_colliders) {
+        bat_collider_count.0.saturating_sub(1)
+    } else if keys.just_pressed(key_bindings.more_bat_colliders) {
+        bat_collider_count.0 + 1
+    } else {
+        return;
+    }
+    .clamp(MIN_BAT_COLLIDERS, MAX_BAT_COLLIDERS);
+
+    if count != bat_collider_count.0 {
+        bat_collider_count.0 = count;
+    }
+    announcement.text = format!("{} bat points", count);
+    announcement.timer = 1.0;
+}
+
+fn toggle_vsync(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut vsync: ResMut<Vsync>,
+) {
+    if keys.just_pressed(key_bindings.toggle_vsync) {
+        vsync.0 = !vsync.0;
+        save_config("ld51_vsync", &*vsync);
+    }
+}
+
+fn cycle_camera_preset(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut camera_preset: ResMut<CameraPreset>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.cycle_camera_preset) {
+        camera_preset.0 = (camera_preset.0 + 1) % CAMERA_PRESETS.len();
+        save_config("ld51_camera_preset", &*camera_preset);
+
+        announcement.text = format!("Camera {}", CAMERA_PRESETS[camera_preset.0].0);
+        announcement.timer = 1.5;
+    }
+}
+
+fn cycle_frame_cap(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut frame_cap: ResMut<FrameCap>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if keys.just_pressed(key_bindings.cycle_frame_cap) {
+        let next = FRAME_CAPS
+            .iter()
+            .position(|cap| *cap == frame_cap.0)
+            .map_or(0, |i| (i + 1) % FRAME_CAPS.len());
+        frame_cap.0 = FRAME_CAPS[next];
+        save_config("ld51_frame_cap", &*frame_cap);
+
+        announcement.text = match frame_cap.0 {
+            Some(cap) => format!("Frame rate capped at {:.0} fps", cap),
+            None => "Frame rate uncapped".to_string(),
+        };
+        announcement.timer = 1.5;
+    }
+}
+
+fn start_game_over_timer(
+    restart_delay: Res<RestartDelay>,
+    mut game_over_timer: ResMut<GameOverTimer>,
+    mut confirm_quit: ResMut<ConfirmQuit>,
+) {
+    game_over_timer.0 = restart_delay.0;
+    confirm_quit.0 = false;
+}
+
+fn restart(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut game_over_timer: ResMut<GameOverTimer>,
+    mut new_game_events: EventWriter<NewGameEvent>,
+) {
+    if game_over_timer.0 > 0.0 {
+        game_over_timer.0 -= time.delta_seconds();
+        return;
+    }
+
+    if keys.just_pressed(key_bindings.restart) {
+        new_game_events.send(NewGameEvent {
+            seed: rand::random(),
+            replay: None,
+        });
+    }
+}
+
+// escape twice to quit, browsers just keep the tab open
+fn quit(
+    keys: Res<Input<KeyCode>>,
+    game_over_timer: Res<GameOverTimer>,
+    mut confirm_quit: ResMut<ConfirmQuit>,
+    mut app_exit_events: EventWriter<AppExit>,
+) {
+    if cfg!(target_family = "wasm")
+        || game_over_timer.0 > 0.0
+        || !keys.just_pressed(KeyCode::Escape)
+    {
+        return;
+    }
+
+    if confirm_quit.0 {
+        app_exit_events.send(AppExit);
+    } else {
+        confirm_quit.0 = true;
+    }
+}
+
+fn share_run(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    game_seed: Res<GameSeed>,
+    input_recording: Res<InputRecording>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if !keys.just_pressed(key_bindings.share_run) {
+        return;
+    }
+
+    let code = encode_share_code(&ShareCode {
+        seed: game_seed.0,
+        frames: input_recording.0.clone(),
+    });
+    info!("share code: {}", code);
+    save_config("ld51_share_code", &code);
+
+    announcement.text = "Share code saved".to_string();
+    announcement.timer = 1.5;
+}
+
+// photo mode takes every key press and mouse drag, hotkeys stay quiet while framing a shot
+fn photo_mode_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    key_bindings: Res<KeyBindings>,
+    mut app_state: ResMut<State<AppState>>,
+    mut photo_input: ResMut<PhotoInput>,
+) {
+    // no photos of the settings menu
+    if *app_state.current() == AppState::Settings {
+        mouse_motion.clear();
+        return;
+    }
+
+    let in_photo_mode = *app_state.current() == AppState::PhotoMode;
+    if keys.just_pressed(key_bindings.photo_mode)
+        || (in_photo_mode && keys.just_pressed(KeyCode::Escape))
+    {
+        // the same escape would otherwise open the pause menu right after
+        keys.clear_just_pressed(key_bindings.photo_mode);
+        keys.clear_just_pressed(KeyCode::Escape);
+        if in_photo_mode {
+            app_state.pop().unwrap();
+        } else {
+            app_state.push(AppState::PhotoMode).unwrap();
+        }
+        *photo_input = PhotoInput::default();
+        mouse_motion.clear();
+        return;
+    }
+
+    if !in_photo_mode {
+        mouse_motion.clear();
+        return;
+    }
+
+    let axis = |negative: KeyCode, positive: KeyCode| {
+        keys.pressed(positive) as i8 as f32 - keys.pressed(negative) as i8 as f32
+    };
+    photo_input.movement = vec3(
+        axis(KeyCode::A, KeyCode::D),
+        axis(KeyCode::Q, KeyCode::E),
+        axis(KeyCode::W, KeyCode::S),
+    );
+    photo_input.look = Vec2::ZERO;
+    if mouse_buttons.pressed(MouseButton::Left) {
+        for motion in mouse_motion.iter() {
+            photo_input.look += motion.delta;
+        }
+    } else {
+        mouse_motion.clear();
+    }
+    keys.clear();
+}
+
+// arrows or the d-pad move through the menu, and like photo mode it swallows every other key
+fn settings_input(
+    mut keys: ResMut<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    key_bindings: Res<KeyBindings>,
+    mut app_state: ResMut<State<AppState>>,
+    mut settings_menu: ResMut<SettingsMenu>,
+    mut settings: Settings,
+) {
+    let button = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(*gamepad, button_type)))
+    };
+    let in_settings = *app_state.current() == AppState::Settings;
+
+    if keys.just_pressed(key_bindings.settings)
+        || button(GamepadButtonType::Start)
+        || (in_settings && (keys.just_pressed(KeyCode::Escape) || button(GamepadButtonType::East)))
+    {
+        if in_settings {
+            app_state.pop().unwrap();
+            keys.clear();
+        } else if *app_state.current() != AppState::PhotoMode {
+            app_state.push(AppState::Settings).unwrap();
+            keys.clear();
+        }
+        return;
+    }
+
+    if !in_settings {
+        return;
+    }
+
+    let items = SettingsItem::ALL.len();
+    if keys.just_pressed(KeyCode::Up) || button(GamepadButtonType::DPadUp) {
+        settings_menu.selected = (settings_menu.selected + items - 1) % items;
+    }
+    if keys.just_pressed(KeyCode::Down) || button(GamepadButtonType::DPadDown) {
+        settings_menu.selected = (settings_menu.selected + 1) % items;
+    }
+
+    let direction = (keys.just_pressed(KeyCode::Right) || button(GamepadButtonType::DPadRight))
+        as i32
+        - (keys.just_pressed(KeyCode::Left) || button(GamepadButtonType::DPadLeft)) as i32;
+    if direction != 0 {
+        settings.adjust(SettingsItem::ALL[settings_menu.selected], direction);
+    }
+
+    keys.clear();
+}
+
+// typing swallows key presses so hotkeys don't fire, enter watches the code
+fn type_share_code(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut characters: EventReader<ReceivedCharacter>,
+    key_bindings: Res<KeyBindings>,
+    mut share_code_input: ResMut<ShareCodeInput>,
+    mut new_game_events: EventWriter<NewGameEvent>,
+    mut announcement: ResMut<Announcement>,
+) {
+    if !share_code_input.active {
+        characters.clear();
+        if keys.just_pressed(key_bindings.watch_run) {
+            keys.clear_just_pressed(key_bindings.watch_run);
+            *share_code_input = ShareCodeInput {
+                active: true,
+                text: String::new(),
+            };
+        }
+        return;
+    }
+
+    for character in characters.iter() {
+        if character.char.is_ascii_alphanumeric() || matches!(character.char, '-' | '_') {
+            share_code_input.text.push(character.char);
+        }
+    }
+
+    let submit = keys.just_pressed(KeyCode::Return);
+    let cancel = keys.just_pressed(KeyCode::Escape);
+    if keys.just_pressed(KeyCode::Back) {
+        share_code_input.text.pop();
+    }
+    keys.clear();
+
+    if cancel {
+        share_code_input.active = false;
+    }
+    if !submit {
+        return;
+    }
+
+    // an empty field watches the last saved code
+    let text = if share_code_input.text.is_empty() {
+        load_config::<String>("ld51_share_code").unwrap_or_default()
+    } else {
+        share_code_input.text.clone()
+    };
+    share_code_input.active = false;
+
+    match decode_share_code(&text) {
+        Ok(share_code) => new_game_events.send(NewGameEvent {
+            seed: share_code.seed,
+            replay: Some(share_code.frames),
+        }),
+        Err(e) => {
+            warn!("bad share code: {}", e);
+            announcement.text = "Invalid share code".to_string();
+            announcement.timer = 1.5;
+        }
+    }
+}
+
+fn toggle_target_sequence(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut target_sequence: ResMut<TargetSequence>,
+    mut q_targets: Query<&mut Visibility, With<Target>>,
+) {
+    if !keys.just_pressed(key_bindings.toggle_targets) {
+        return;
+    }
+
+    let enabled = !target_sequence.enabled;
+    *target_sequence = TargetSequence {
+        enabled,
+        ..default()
+    };
+    for mut visibility in q_targets.iter_mut() {
+        visibility.is_visible = target_sequence.enabled;
+    }
+}
+
+fn toggle_gravity_wells(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut q_wells: Query<&mut Visibility, With<GravityWell>>,
+) {
+    if !keys.just_pressed(key_bindings.toggle_gravity_wells) {
+        return;
+    }
+
+    for mut visibility in q_wells.iter_mut() {
+        visibility.is_visible = !visibility.is_visible;
+    }
+}
+
+// hold to stop the pitch nearest the plate where it is, let go and it carries on
+fn hold_study_freeze(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    pitch_config: Res<PitchConfig>,
+    mut study_freeze: ResMut<StudyFreeze>,
+    mut stats: ResMut<Stats>,
+    mut announcement: ResMut<Announcement>,
+    mut q_balls: Query<(Entity, &Transform, &mut Velocity, &Status)>,
+) {
+    if keys.just_pressed(key_bindings.study_freeze) && study_freeze.ball.is_none() {
+        let nearest = q_balls
+            .iter_mut()
+            .filter(|(.., status)| status.0 == BallStatus::Thrown)
+            .min_by(|(_, a, ..), (_, b, ..)| {
+                a.translation
+                    .distance(pitch_config.strike_point)
+                    .total_cmp(&b.translation.distance(pitch_config.strike_point))
+            });
+        if let Some((entity, _, mut velocity, _)) = nearest {
+            *study_freeze = StudyFreeze {
+                ball: Some(entity),
+                velocity: std::mem::take(&mut velocity.0),
+            };
+
+            // practice, not a real run
+            if !stats.sandbox {
+                stats.sandbox = true;
+                announcement.text = "Pitch frozen, this run won't count".to_string();
+                announcement.timer = 1.5;
+            }
+        }
+    }
+
+    if !keys.pressed(key_bindings.study_freeze) {
+        if let Some(ball) = study_freeze.ball.take() {
+            if let Ok((_, _, mut velocity, _)) = q_balls.get_mut(ball) {
+                velocity.0 = study_freeze.velocity;
+            }
+        }
+    }
+}
+
+fn toggle_backboard(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut q_backboards: Query<&mut Visibility, With<Backboard>>,
+) {
+    if !keys.just_pressed(key_bindings.toggle_backboard) {
+        return;
+    }
+
+    for mut visibility in q_backboards.iter_mut() {
+        visibility.is_visible = !visibility.is_visible;
+    }
+}
+
+// walks through every binding, assigning the next key pressed
+fn rebind_keys(
+    mut keys: ResMut<Input<KeyCode>>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let index = match rebinding.0 {
+        Some(index) => index,
+        None => {
+            if keys.just_pressed(key_bindings.rebind) {
+                keys.clear_just_pressed(key_bindings.rebind);
+                rebinding.0 = Some(0);
+            }
+            return;
+        }
+    };
+
+    let key = match keys.get_just_pressed().next().copied() {
+        Some(key) => key,
+        None => return,
+    };
+
+    // keep the press from also triggering the action
+    keys.clear_just_pressed(key);
+
+    if key != KeyCode::Escape {
+        *key_bindings.entries_mut()[index].1 = key;
+    }
+
+    if index + 1 < key_bindings.entries().len() {
+        rebinding.0 = Some(index + 1);
+    } else {
+        rebinding.0 = None;
+        save_config("ld51_key_bindings", &*key_bindings);
+    }
+}
+
+fn toggle_cinematic_hits(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut cinematic_hits: ResMut<CinematicHits>,
+) {
+    if keys.just_pressed(key_bindings.toggle_cinematic_hits) {
+        cinematic_hits.0 = !cinematic_hits.0;
+    }
+}
+
+fn toggle_hit_stop(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut hit_stop_mode: ResMut<HitStopMode>,
+) {
+    if keys.just_pressed(key_bindings.toggle_hit_stop) {
+        *hit_stop_mode = match *hit_stop_mode {
+            HitStopMode::Global => HitStopMode::Local,
+            HitStopMode::Local => HitStopMode::Global,
+        };
+    }
+}
+
+fn toggle_reduce_motion(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut reduce_motion: ResMut<ReduceMotion>,
+) {
+    if keys.just_pressed(key_bindings.toggle_reduce_motion) {
+        reduce_motion.0 = !reduce_motion.0;
+        save_config("ld51_reduce_motion", &*reduce_motion);
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+mod audio;
+mod components;
+mod config;
+#[cfg(feature = "debug")]
+mod debug;
+mod effects;
+mod game;
+mod headless;
+mod input;
+mod physics;
+mod spawn;
+mod ui;
+
+use audio::GameAudioPlugin;
+use components::*;
+#[cfg(feature = "debug")]
+use debug::DebugPlugin;
+use effects::EffectsPlugin;
+use game::GamePlugin;
+use input::InputPlugin;
+use physics::PhysicsPlugin;
+use spawn::SpawnPlugin;
+use ui::HudPlugin;
+
+pub use components::SessionBests;
+pub use headless::run_headless;
+
+// the whole game, add it after DefaultPlugins
+pub struct Ld51Plugin;
+
+impl Plugin for Ld51Plugin {
+    fn build(&self, app: &mut App) {
+        let seed = rand::random();
+        app.insert_resource(GameRng(StdRng::seed_from_u64(seed)))
+            .insert_resource(AiRng::new(seed))
+            .insert_resource(GameSeed(seed))
+            .insert_resource(CameraIntro::default())
+            .add_plugin(GameplayPlugin)
+            .add_plugin(InputPlugin)
+            .add_plugin(GameAudioPlugin)
+            .add_plugin(EffectsPlugin)
+            .add_plugin(HudPlugin);
+
+        #[cfg(feature = "debug")]
+        app.add_plugin(DebugPlugin);
+    }
+}
+
+// everything that decides the outcome of a game, nothing that needs a window, input or audio
+pub(crate) struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(GamePlugin)
+            .add_plugin(PhysicsPlugin)
+            .add_plugin(SpawnPlugin);
+    }
+}
+
+// for code embedding the game, or driving it from outside its systems
+pub trait GameApi {
+    fn current_score(&self) -> u32;
+    fn outs(&self) -> u32;
+    fn inning(&self) -> u32;
+    fn session_bests(&self) -> SessionBests;
+    fn is_game_over(&self) -> bool;
+    // fresh game on a new seed, takes effect on the next update
+    fn reset_session(&mut self);
+}
+
+impl GameApi for World {
+    fn current_score(&self) -> u32 {
+        self.resource::<Score>().0
+    }
+
+    fn outs(&self) -> u32 {
+        self.resource::<Outs>().0
+    }
+
+    fn inning(&self) -> u32 {
+        self.resource::<Inning>().number
+    }
+
+    fn session_bests(&self) -> SessionBests {
+        *self.resource::<SessionBests>()
+    }
+
+    fn is_game_over(&self) -> bool {
+        *self.resource::<State<AppState>>().current() == AppState::GameOver
+    }
+
+    fn reset_session(&mut self) {
+        self.resource_mut::<Events<NewGameEvent>>()
+            .send(NewGameEvent {
+                seed: rand::random(),
+                replay: None,
+            });
+    }
+}
@@ -0,0 +1,1380 @@
+use crate::{components::*, config::load_config, game::fixed_step};
+use bevy::{
+    math::{vec2, vec3},
+    prelude::*,
+    utils::HashMap,
+};
+
+// ball flight, bat collisions and the bat itself
+pub(crate) struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PauseTimer(0.0))
+            .insert_resource(HitStopMode::Global)
+            .insert_resource(TimeScale::default())
+            .insert_resource(Sandbox(false))
+            .insert_resource(StudyFreeze::default())
+            .insert_resource(load_config::<PhysicsConfig>("ld51_physics").unwrap_or_default())
+            .insert_resource(MaxBallSpeed(20.0))
+            .insert_resource(load_config::<SlowZone>("ld51_slow_zone").unwrap_or_default())
+            .insert_resource(BatWeight::Medium)
+            .insert_resource(BatColliderCount(7))
+            .insert_resource(BatConfig {
+                floppy: false,
+                spring_stiffness: 400.0,
+                spring_damping: 20.0,
+            })
+            .add_system_set(
+                // physics should only run when not paused
+                SystemSet::on_update(AppState::InGame)
+                    .with_system(physics)
+                    .with_system(update_bat_transform)
+                    .with_system(update_bat_segments)
+                    .with_system(shrink_bat),
+            )
+            .add_system(apply_bat_mode)
+            .add_system(rebuild_bat_colliders)
+            .add_system_set(SystemSet::on_enter(AppState::HitPause).with_system(start_pause_timer))
+            .add_system_set(
+                SystemSet::on_update(AppState::HitPause).with_system(update_pause_timer),
+            )
+            .add_system_set(
+                // easiest to have this framerate independent
+                SystemSet::new()
+                    .with_run_criteria(fixed_step("historic velocity", 1.0 / 60.0))
+                    .with_system(update_collider_historic_velocity),
+            );
+
+        // physics checks these even when stepping is off
+        #[cfg(feature = "debug")]
+        app.insert_resource(StepMode(false))
+            .insert_resource(StepFrame::default());
+    }
+}
+
+fn update_pause_timer(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut pause_timer: ResMut<PauseTimer>,
+    mut state: ResMut<State<AppState>>,
+) {
+    pause_timer.0 -= time.delta_seconds() * time_scale.debug;
+
+    if pause_timer.0 < 0.0 {
+        state.set(AppState::InGame).unwrap();
+    }
+}
+
+fn start_pause_timer(mut pause_timer: ResMut<PauseTimer>) {
+    pause_timer.0 = PAUSE_TIME;
+}
+
+fn physics(
+    mut app_state: ResMut<State<AppState>>,
+    (time, time_scale): (Res<Time>, Res<TimeScale>),
+    #[cfg(feature = "debug")] step_mode: Res<StepMode>,
+    #[cfg(feature = "debug")] step_frame: Res<StepFrame>,
+    (mut score, mut last_hit): (ResMut<Score>, ResMut<LastHit>),
+    // tuning
+    (
+        physics_config,
+        max_ball_speed,
+        bat_weight,
+        wind,
+        rule_effects,
+        aim_assist,
+        slow_zone,
+        sandbox,
+    ): (
+        Res<PhysicsConfig>,
+        Res<MaxBallSpeed>,
+        Res<BatWeight>,
+        Res<Wind>,
+        Res<RuleEffects>,
+        Res<AimAssist>,
+        Res<SlowZone>,
+        Res<Sandbox>,
+    ),
+    pitch_config: Res<PitchConfig>,
+    (cinematic_hits, hit_stop_mode, study_freeze): (
+        Res<CinematicHits>,
+        Res<HitStopMode>,
+        Res<StudyFreeze>,
+    ),
+    (mut hit_events, mut particle_events): (EventWriter<HitEvent>, EventWriter<ParticleSpec>),
+    mut q_balls: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &Size,
+        &mut Status,
+        &Curve,
+        &mut BounceCount,
+        &mut InSlowZone,
+        &mut HitStop,
+        &mut FlightTime,
+        &BallKind,
+        &mut ClosestApproach,
+        Option<&Hazard>,
+        Option<&Accelerating>,
+    )>,
+    q_colliders: Query<(Entity, &GlobalTransform, &HistoricVelocity), With<BatCollider>>,
+    q_targets: Query<(&Transform, &Visibility), (With<Target>, Without<Status>)>,
+    q_wells: Query<(&Transform, &GravityWell, &Visibility), Without<Status>>,
+    q_backboards: Query<(&Transform, &Backboard, &Visibility), Without<Status>>,
+) {
+    let dt = time.delta_seconds() * time_scale.simulation();
+
+    // only advance on step key while stepping
+    #[cfg(feature = "debug")]
+    let dt = if step_mode.0 {
+        if !step_frame.advance {
+            return;
+        }
+        STEP_DT
+    } else {
+        dt
+    };
+
+    for (
+        entity,
+        mut transform,
+        mut velocity,
+        size,
+        mut status,
+        curve,
+        mut bounce_count,
+        mut in_slow_zone,
+        mut hit_stop,
+        mut flight_time,
+        kind,
+        mut closest_approach,
+        hazard,
+        accelerating,
+    ) in q_balls.iter_mut()
+    {
+        // frozen by a local hit-stop, everything else keeps moving
+        if hit_stop.0 > 0.0 {
+            hit_stop.0 -= dt;
+            continue;
+        }
+
+        // held for study, the bat can't touch it until it's let go
+        if study_freeze.ball == Some(entity) {
+            continue;
+        }
+
+        // slow pitches near the plate, the speed comes back on the way out
+        let in_zone = slow_zone.enabled
+            && status.0 == BallStatus::Thrown
+            && transform.translation.distance(pitch_config.strike_point) < slow_zone.radius;
+        let factor = slow_zone.factor.clamp(0.1, 1.0);
+        if in_zone != in_slow_zone.0 {
+            velocity.0 *= if in_zone { factor } else { 1.0 / factor };
+            in_slow_zone.0 = in_zone;
+        }
+
+        // same path at lower speed needs the forces scaled by factor squared
+        let force_scale = if in_slow_zone.0 { factor * factor } else { 1.0 };
+
+        velocity.0 = apply_gravity(
+            velocity.0,
+            status.0,
+            *kind,
+            &physics_config.gravity_model,
+            &mut flight_time.0,
+            rule_effects.gravity_scale * force_scale,
+            dt,
+        );
+
+        // break only while on the way in
+        if status.0 == BallStatus::Thrown {
+            velocity.0 += curve.0 * dt * force_scale;
+
+            // pressure balls keep speeding up until they're dealt with
+            if let Some(accelerating) = accelerating {
+                velocity.0 = accelerate(velocity.0, accelerating.rate, dt);
+            }
+        }
+
+        // wind and wells only carry hit balls, pitches stay readable
+        if status.0 == BallStatus::Hit {
+            velocity.0 += wind.0 * dt;
+            velocity.0 += well_acceleration(
+                transform.translation,
+                q_wells
+                    .iter()
+                    .filter(|(.., visibility)| visibility.is_visible)
+                    .map(|(well_transform, well, _)| (well_transform.translation, well)),
+            ) * dt;
+        }
+
+        // move & bounce on ground
+        let (mut new_translation, new_velocity) = step_with_ground(
+            transform.translation,
+            velocity.0,
+            size.0,
+            physics_config.ground_restitution,
+            physics_config.ground_tangent_restitution,
+            dt,
+        );
+
+        // count real bounces, not a ball rolling along the ground
+        if status.0 == BallStatus::Hit && velocity.0.y < -RICOCHET_MIN_SPEED && new_velocity.y > 0.0
+        {
+            bounce_count.0 += 1;
+        }
+        velocity.0 = new_velocity;
+
+        // friction slows balls rolling along the ground
+        if new_translation.y <= size.0 + GROUND_CONTACT_MARGIN {
+            velocity.0 = apply_ground_friction(
+                velocity.0,
+                physics_config.ground_friction * GRAVITY * rule_effects.gravity_scale,
+                dt,
+            );
+        }
+
+        // bounce off visible targets
+        for (target_transform, visibility) in q_targets.iter() {
+            if !visibility.is_visible {
+                continue;
+            }
+
+            let offset = new_translation - target_transform.translation;
+            if offset.length() < TARGET_RADIUS + size.0 {
+                let normal = offset.normalize_or_zero();
+                new_translation = target_transform.translation + normal * (TARGET_RADIUS + size.0);
+                velocity.0 = reflect(velocity.0, normal, physics_config.target_restitution);
+            }
+        }
+
+        // pitches ricochet off visible backboards, hit balls have left the plate behind
+        if status.0 == BallStatus::Thrown {
+            for (board_transform, board, visibility) in q_backboards.iter() {
+                if !visibility.is_visible {
+                    continue;
+                }
+
+                if let Some((translation, new_velocity)) = bounce_off_backboard(
+                    transform.translation,
+                    new_translation,
+                    velocity.0,
+                    size.0,
+                    board_transform,
+                    board,
+                ) {
+                    new_translation = translation;
+                    velocity.0 = new_velocity;
+                }
+            }
+        }
+
+        // bat collision, a swing fast enough right now or soon after the ball passed within reach
+        let contact = if status.0 == BallStatus::Thrown {
+            bat_contact(
+                transform.translation,
+                velocity.0,
+                physics_config.hit_radius(size.0, &sandbox) * aim_assist.0,
+                &physics_config,
+                dt,
+                &mut closest_approach,
+                &q_colliders,
+            )
+        } else {
+            None
+        };
+
+        if let Some((ball_pos, pitch_velocity, decaying_vel)) = contact {
+            // a late hit happens back where the ball was closest
+            new_translation = ball_pos;
+            velocity.0 = pitch_velocity;
+            closest_approach.0 = None;
+
+            status.0 = BallStatus::Hit;
+            // the hit sets a fresh speed, nothing to restore
+            in_slow_zone.0 = false;
+            if hazard.is_none() {
+                score.0 += (hit_score(size.0) as f32 * kind.score_scale()).round() as u32;
+            }
+            let swing = decaying_vel * bat_weight.power();
+            let hit_power = swing.length();
+            last_hit.power = hit_power;
+
+            let power_hit = hazard.is_none() && hit_power > physics_config.power_hit_threshold;
+            if power_hit && !cinematic_hits.0 {
+                match *hit_stop_mode {
+                    HitStopMode::Global => app_state.set(AppState::HitPause).unwrap(),
+                    HitStopMode::Local => hit_stop.0 = HIT_STOP_TIME,
+                }
+            }
+
+            let new_velocity = hit_velocity(velocity.0, swing, power_hit);
+            velocity.0 = new_velocity.clamp_length_max(max_ball_speed.0);
+            last_hit.exit_speed = velocity.0.length();
+            last_hit.launch_angle = velocity
+                .0
+                .y
+                .atan2(vec2(velocity.0.x, velocity.0.z).length())
+                .to_degrees();
+
+            hit_events.send(match hazard {
+                Some(_) => HitEvent::Hazard { ball: entity },
+                None => HitEvent::Ball {
+                    ball: entity,
+                    power_hit,
+                    exit_velocity: velocity.0,
+                    contact: ball_pos,
+                },
+            });
+
+            // contact burst
+            particle_events.send(ParticleSpec {
+                position: ball_pos,
+                direction: new_velocity.normalize_or_zero(),
+                count: 12,
+                spread: 1.0,
+                speed: 2.0,
+                size: 0.02,
+                color: if hazard.is_some() {
+                    Color::RED
+                } else {
+                    Color::WHITE
+                },
+                gravity: 4.0,
+                lifetime: 0.4,
+            });
+        }
+
+        // apply velocity
+        transform.translation = new_translation;
+    }
+}
+
+// the ball position, pitch velocity and swing of a hit this frame, if any
+// a collider in reach swinging fast enough hits now, otherwise the nearest one in reach is
+// remembered and hits back at that point if it speeds up within the leniency window
+fn bat_contact(
+    ball_pos: Vec3,
+    ball_velocity: Vec3,
+    hit_radius: f32,
+    physics_config: &PhysicsConfig,
+    dt: f32,
+    closest_approach: &mut ClosestApproach,
+    q_colliders: &Query<(Entity, &GlobalTransform, &HistoricVelocity), With<BatCollider>>,
+) -> Option<(Vec3, Vec3, Vec3)> {
+    let fast_enough = |historic: &HistoricVelocity| {
+        historic.decaying_vel.length() >= physics_config.min_swing_speed
+    };
+
+    let in_reach = q_colliders
+        .iter()
+        .map(|(collider, global_transform, historic)| {
+            let distance = ball_pos.distance(global_transform.translation());
+            (collider, distance, historic)
+        })
+        .filter(|(_, distance, _)| *distance < hit_radius)
+        .collect::<Vec<_>>();
+
+    // the nearest collider swinging fast enough hits, so the contact point doesn't depend on
+    // the order colliders come in
+    let swinging = in_reach
+        .iter()
+        .filter(|(.., historic)| fast_enough(historic))
+        .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b));
+    if let Some((.., historic)) = swinging {
+        return Some((ball_pos, ball_velocity, historic.decaying_vel));
+    }
+    let nearest = in_reach
+        .iter()
+        .map(|(collider, distance, _)| (*collider, *distance))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    // age the remembered approach and drop it once the window has passed
+    if let Some(approach) = &mut closest_approach.0 {
+        approach.age += dt;
+        if approach.age > physics_config.hit_leniency {
+            closest_approach.0 = None;
+        }
+    }
+
+    if let Some((collider, distance)) = nearest {
+        let closer = closest_approach
+            .0
+            .as_ref()
+            .map_or(true, |approach| distance < approach.distance);
+        if closer {
+            closest_approach.0 = Some(Approach {
+                collider,
+                distance,
+                position: ball_pos,
+                velocity: ball_velocity,
+                age: 0.0,
+            });
+        }
+    }
+
+    let approach = closest_approach.0.as_ref()?;
+    let (_, _, historic) = q_colliders.get(approach.collider).ok()?;
+    fast_enough(historic).then(|| (approach.position, approach.velocity, historic.decaying_vel))
+}
+
+// the ball off the bat, before the speed cap
+fn hit_velocity(pitch_velocity: Vec3, swing: Vec3, power_hit: bool) -> Vec3 {
+    // bounce back based on hit_power
+    let mut new_velocity = -pitch_velocity * swing.length() * 4.0;
+
+    // affected by bat vector
+    new_velocity += swing * 15.0;
+    new_velocity += swing_pull(pitch_velocity, swing);
+
+    new_velocity.y *= 0.5;
+
+    if power_hit {
+        new_velocity *= 1.2;
+    }
+    new_velocity
+}
+
+// smaller balls score more
+pub(crate) fn hit_score(size: f32) -> u32 {
+    (BASE_HIT_SCORE * BASE_BALL_SIZE / size).round() as u32
+}
+
+// the part of the swing that sweeps across the pitch's path, pulling the ball that way
+// swinging left to right sends the ball to the right field and vice versa
+// the sign follows the swing, the pull points the same way as its sideways part
+fn swing_pull(pitch_velocity: Vec3, swing: Vec3) -> Vec3 {
+    let across = pitch_velocity.cross(Vec3::Y).normalize_or_zero();
+    across * swing.dot(across) * SWING_PULL
+}
+
+// one step of gravity, hit balls count their flight time and follow the gravity model
+// scale is whatever else weighs on the ball, rule effects and the slow zone
+fn apply_gravity(
+    velocity: Vec3,
+    status: BallStatus,
+    kind: BallKind,
+    gravity_model: &GravityModel,
+    flight_time: &mut f32,
+    scale: f32,
+    dt: f32,
+) -> Vec3 {
+    let gravity_model_scale = if status == BallStatus::Hit {
+        *flight_time += dt;
+        gravity_model.scale(*flight_time)
+    } else {
+        1.0
+    };
+    let gravity = GRAVITY * kind.gravity_scale() * scale * gravity_model_scale;
+    velocity - vec3(0.0, gravity * dt, 0.0)
+}
+
+// rate is the fraction of its speed a pressure ball gains each second, compounding
+fn accelerate(velocity: Vec3, rate: f32, dt: f32) -> Vec3 {
+    velocity * (1.0 + rate * dt)
+}
+
+fn well_acceleration<'a>(
+    position: Vec3,
+    wells: impl Iterator<Item = (Vec3, &'a GravityWell)>,
+) -> Vec3 {
+    wells
+        .map(|(well_position, well)| {
+            let offset = well_position - position;
+            let distance = offset.length();
+            if distance >= well.radius {
+                return Vec3::ZERO;
+            }
+            offset.normalize_or_zero() * well.strength * (1.0 - distance / well.radius)
+        })
+        .sum()
+}
+
+// integrate one step against the ground plane, bouncing at the exact crossing
+// point and spending the rest of the step moving away from it
+fn step_with_ground(
+    translation: Vec3,
+    velocity: Vec3,
+    size: f32,
+    restitution: f32,
+    tangent_restitution: f32,
+    dt: f32,
+) -> (Vec3, Vec3) {
+    let new_translation = translation + velocity * dt;
+    if new_translation.y >= size {
+        return (new_translation, velocity);
+    }
+
+    // fraction of the step taken before touching the ground
+    let t = if velocity.y < 0.0 {
+        ((translation.y - size) / (-velocity.y * dt)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let mut contact = translation + velocity * dt * t;
+    contact.y = size;
+
+    // the ground takes energy out of the normal, friction out of the rest
+    // a ball resting on the ground touches it every step, rolling is left to ground_friction
+    let tangent_restitution = if -velocity.y > RICOCHET_MIN_SPEED {
+        tangent_restitution
+    } else {
+        1.0
+    };
+    let bounced = vec3(
+        velocity.x * tangent_restitution,
+        -velocity.y * restitution,
+        velocity.z * tangent_restitution,
+    );
+
+    let mut end = contact + bounced * dt * (1.0 - t);
+    end.y = end.y.max(size);
+
+    (end, bounced)
+}
+
+// take deceleration * dt off the horizontal speed, stopping at rest rather than reversing
+fn apply_ground_friction(velocity: Vec3, deceleration: f32, dt: f32) -> Vec3 {
+    let horizontal = vec2(velocity.x, velocity.z);
+    let speed = horizontal.length();
+    if speed <= 0.0 {
+        return velocity;
+    }
+
+    let slowed = horizontal * ((speed - deceleration * dt).max(0.0) / speed);
+    vec3(slowed.x, velocity.y, slowed.y)
+}
+
+// reflect the part of velocity heading into a surface, scaled by restitution
+// a ball crossing into the front of the board is put back on its face and reflected
+// about its normal, checked along the whole step so fast pitches can't tunnel through
+fn bounce_off_backboard(
+    translation: Vec3,
+    new_translation: Vec3,
+    velocity: Vec3,
+    size: f32,
+    board_transform: &Transform,
+    board: &Backboard,
+) -> Option<(Vec3, Vec3)> {
+    let depth = (translation - board_transform.translation).dot(board.normal);
+    let new_depth = (new_translation - board_transform.translation).dot(board.normal);
+    if depth < 0.0 || new_depth >= size || velocity.dot(board.normal) >= 0.0 {
+        return None;
+    }
+
+    let local =
+        board_transform.rotation.inverse() * (new_translation - board_transform.translation);
+    if local.x.abs() > board.half_extents.x || local.y.abs() > board.half_extents.y {
+        return None;
+    }
+
+    Some((
+        new_translation + board.normal * (size - new_depth),
+        reflect(velocity, board.normal, board.restitution),
+    ))
+}
+
+fn reflect(velocity: Vec3, normal: Vec3, restitution: f32) -> Vec3 {
+    let into_surface = velocity.dot(normal);
+    if into_surface >= 0.0 {
+        return velocity;
+    }
+
+    velocity - (1.0 + restitution) * into_surface * normal
+}
+
+// velocity that carries a ball from origin to target in flight_time under gravity and curve
+pub(crate) fn pitch_velocity(
+    origin: Vec3,
+    target: Vec3,
+    flight_time: f32,
+    curve: Vec3,
+    gravity: f32,
+) -> Vec3 {
+    let acceleration = curve - vec3(0.0, gravity, 0.0);
+    (target - origin) / flight_time - 0.5 * acceleration * flight_time
+}
+
+fn update_collider_historic_velocity(
+    #[cfg(feature = "debug")] step_mode: Res<StepMode>,
+    mut q: Query<(&BatCollider, &GlobalTransform, &mut HistoricVelocity)>,
+) {
+    // step mode samples on its own
+    #[cfg(feature = "debug")]
+    if step_mode.0 {
+        return;
+    }
+
+    for (_collider, global_transform, mut historical_velocity) in q.iter_mut() {
+        sample_historic_velocity(global_transform, &mut historical_velocity);
+    }
+}
+
+pub(crate) fn sample_historic_velocity(
+    global_transform: &GlobalTransform,
+    historical_velocity: &mut HistoricVelocity,
+) {
+    let new_pos = global_transform.translation();
+    let diff = new_pos - historical_velocity.previous_pos;
+    historical_velocity.previous_pos = new_pos;
+
+    // increase by diff
+    historical_velocity.decaying_vel += diff;
+
+    // decay
+    historical_velocity.decaying_vel *= 0.7;
+}
+
+// where the ball passes closest to `point`, and how long until it gets there
+pub(crate) fn predict_closest_pass(
+    mut position: Vec3,
+    mut velocity: Vec3,
+    curve: Vec3,
+    gravity: f32,
+    point: Vec3,
+) -> (Vec3, f32) {
+    let dt = 1.0 / 120.0;
+    let mut best = (position, 0.0);
+    let mut t = 0.0;
+    while t < 3.0 {
+        velocity += (curve - vec3(0.0, gravity, 0.0)) * dt;
+        position += velocity * dt;
+        t += dt;
+
+        if position.distance(point) < best.0.distance(point) {
+            best = (position, t);
+        } else if t > best.1 + 0.1 {
+            // moving away again
+            break;
+        }
+    }
+    best
+}
+
+// where a ball comes down on flat ground, and how long until it does
+// ignores wind and the gravity model, close enough to run toward
+pub(crate) fn predict_landing(position: Vec3, velocity: Vec3, gravity: f32) -> (Vec3, f32) {
+    let gravity = gravity.max(0.01);
+    let t = (velocity.y + (velocity.y * velocity.y + 2.0 * gravity * position.y.max(0.0)).sqrt())
+        / gravity;
+    let landing = position + velocity * t;
+    (vec3(landing.x, 0.0, landing.z), t)
+}
+
+// bat height and rotation in player space for an aim
+pub(crate) fn bat_pose(aim: Vec2) -> (f32, Quat) {
+    let y = aim.y - 0.2;
+    let rotation = Quat::from_euler(EulerRot::XYZ, -0.6, 0.1, -0.7)
+        * Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, -aim.x * 2.2 + 0.5);
+    (y, rotation)
+}
+
+fn update_bat_transform(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    bat_weight: Res<BatWeight>,
+    bat_aim: Res<BatAim>,
+    gamepad_aim: Res<GamepadAim>,
+    fielding_state: Res<State<FieldingState>>,
+    mut q_bat: Query<(&mut Transform, &BatOwner), With<Bat>>,
+) {
+    // the aim steers the fielder for now, the bat holds its pose
+    if *fielding_state.current() == FieldingState::Aiming {
+        return;
+    }
+
+    let n = (time.delta_seconds() * time_scale.debug * bat_weight.follow_rate()).min(1.0);
+
+    for (mut bat_transform, owner) in q_bat.iter_mut() {
+        let aim = if owner.0 == 0 {
+            bat_aim.0
+        } else {
+            gamepad_aim.0
+        };
+        let (new_y, new_rotation) = bat_pose(aim);
+
+        // smooth transition to new values
+        bat_transform.translation.y = bat_transform.translation.y * (1.0 - n) + new_y * n;
+        bat_transform.rotation = bat_transform.rotation * (1.0 - n) + new_rotation * n;
+    }
+}
+
+fn apply_bat_mode(
+    mut commands: Commands,
+    bat_config: Res<BatConfig>,
+    q_anchors: Query<(Entity, &BatAnchor, &BatOwner, &GlobalTransform)>,
+    mut q_segments: Query<
+        (
+            Entity,
+            &mut BatSegment,
+            &BatOwner,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<BatVisualPart>,
+    >,
+    mut q_visual: Query<&mut Visibility, With<BatVisualPart>>,
+) {
+    if !bat_config.is_changed() {
+        return;
+    }
+
+    // sampling starts over where the collider now is, the jump from the other bat isn't a swing
+    let at_rest = |position: Vec3| HistoricVelocity {
+        previous_pos: position,
+        decaying_vel: Vec3::ZERO,
+    };
+
+    // move the colliders over to whichever bat is active
+    let mut anchor_positions = HashMap::new();
+    for (entity, anchor, owner, global_transform) in q_anchors.iter() {
+        anchor_positions.insert((*owner, anchor.0), global_transform.translation());
+
+        if bat_config.floppy {
+            commands.entity(entity).remove::<BatCollider>();
+        } else {
+            commands
+                .entity(entity)
+                .insert(BatCollider(anchor.0))
+                .insert(at_rest(global_transform.translation()));
+        }
+    }
+
+    for (entity, mut segment, owner, mut transform, mut visibility) in q_segments.iter_mut() {
+        visibility.is_visible = bat_config.floppy;
+
+        if bat_config.floppy {
+            // start from the rigid pose
+            transform.translation = anchor_positions
+                .get(&(*owner, segment.index))
+                .copied()
+                .unwrap_or_default();
+            segment.velocity = Vec3::ZERO;
+            commands
+                .entity(entity)
+                .insert(BatCollider(segment.index))
+                .insert(at_rest(transform.translation));
+        } else {
+            commands.entity(entity).remove::<BatCollider>();
+        }
+    }
+
+    for mut visibility in q_visual.iter_mut() {
+        visibility.is_visible = !bat_config.floppy;
+    }
+}
+
+// respawns anchors and segments on every bat, the rest of the bats is left alone
+fn rebuild_bat_colliders(
+    mut commands: Commands,
+    bat_collider_count: Res<BatColliderCount>,
+    bat_config: Res<BatConfig>,
+    bat_collider_assets: Res<BatColliderAssets>,
+    q_visual: Query<(Entity, &BatOwner, &GlobalTransform), With<BatVisual>>,
+    q_added: Query<(), Added<BatVisual>>,
+    q_old: Query<Entity, Or<(With<BatAnchor>, With<BatSegment>)>>,
+) {
+    if !bat_collider_count.is_changed() && q_added.is_empty() {
+        return;
+    }
+
+    for entity in q_old.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for (visual, owner, visual_transform) in q_visual.iter() {
+        spawn_bat_colliders(
+            &mut commands,
+            bat_collider_count.0,
+            &bat_config,
+            &bat_collider_assets,
+            visual,
+            *owner,
+            visual_transform,
+        );
+    }
+}
+
+fn spawn_bat_colliders(
+    commands: &mut Commands,
+    count: u32,
+    bat_config: &BatConfig,
+    bat_collider_assets: &BatColliderAssets,
+    visual: Entity,
+    owner: BatOwner,
+    visual_transform: &GlobalTransform,
+) {
+    for i in 0..count {
+        let local = vec3(0.0, -0.4 + 0.9 * i as f32 / (count - 1) as f32, 0.0);
+        let world = visual_transform.compute_matrix().transform_point3(local);
+
+        // start from the current position so the first sample isn't a swing
+        let historic_velocity = || HistoricVelocity {
+            previous_pos: world,
+            decaying_vel: Vec3::ZERO,
+        };
+
+        let anchor = commands
+            .spawn_bundle(PbrBundle {
+                mesh: bat_collider_assets.anchor_mesh.clone(),
+                material: bat_collider_assets.anchor_material.clone(),
+                transform: Transform::from_translation(local),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(BatAnchor(i as i32))
+            .insert(owner)
+            .insert(historic_velocity())
+            .id();
+        if !bat_config.floppy {
+            commands.entity(anchor).insert(BatCollider(i as i32));
+        }
+        commands.entity(visual).add_child(anchor);
+
+        // floppy segments live in world space so they can lag behind
+        let segment = commands
+            .spawn_bundle(PbrBundle {
+                mesh: bat_collider_assets.segment_mesh.clone(),
+                material: bat_collider_assets.segment_material.clone(),
+                transform: Transform::from_translation(world),
+                visibility: Visibility {
+                    is_visible: bat_config.floppy,
+                },
+                ..default()
+            })
+            .insert(BatSegment {
+                index: i as i32,
+                velocity: Vec3::ZERO,
+            })
+            .insert(owner)
+            .insert(historic_velocity())
+            .id();
+        if bat_config.floppy {
+            commands.entity(segment).insert(BatCollider(i as i32));
+        }
+    }
+}
+
+fn update_bat_segments(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    bat_config: Res<BatConfig>,
+    q_anchors: Query<(&BatAnchor, &BatOwner, &GlobalTransform)>,
+    mut q_segments: Query<(&mut BatSegment, &BatOwner, &mut Transform)>,
+) {
+    if !bat_config.floppy {
+        return;
+    }
+
+    let mut anchor_positions = HashMap::new();
+    for (anchor, owner, global_transform) in q_anchors.iter() {
+        anchor_positions.insert((*owner, anchor.0), global_transform.translation());
+    }
+    let anchor_position = |owner: BatOwner, index: i32| {
+        anchor_positions
+            .get(&(owner, index))
+            .copied()
+            .unwrap_or_default()
+    };
+
+    // walk each bat from handle to tip, so each segment follows the one before it
+    let mut segments: Vec<_> = q_segments.iter_mut().collect();
+    segments.sort_by_key(|(segment, owner, _)| (owner.0, segment.index));
+
+    // explicit euler on a stiff spring, a frame hitch in one step would blow it up
+    let dt = (time.delta_seconds() * time_scale.debug).min(MAX_SPRING_DT);
+    let steps = (dt / SPRING_STEP).ceil().max(1.0);
+    let step_dt = dt / steps;
+
+    for _ in 0..steps as u32 {
+        let mut previous_pos = Vec3::ZERO;
+
+        for (segment, owner, transform) in segments.iter_mut() {
+            let i = segment.index;
+            let target = if i == 0 {
+                anchor_position(**owner, 0)
+            } else {
+                previous_pos + (anchor_position(**owner, i) - anchor_position(**owner, i - 1))
+            };
+
+            // damped spring toward target
+            let accel = (target - transform.translation) * bat_config.spring_stiffness
+                - segment.velocity * bat_config.spring_damping;
+            segment.velocity += accel * step_dt;
+            transform.translation += segment.velocity * step_dt;
+
+            previous_pos = transform.translation;
+        }
+    }
+}
+
+fn bat_scale(game_time: f32) -> f32 {
+    (1.0 - game_time * BAT_SHRINK_RATE).max(MIN_BAT_SCALE)
+}
+
+fn shrink_bat(
+    game_time: Res<GameTime>,
+    rule_effects: Res<RuleEffects>,
+    mut bat_shrink_milestone: ResMut<BatShrinkMilestone>,
+    mut announcement: ResMut<Announcement>,
+    mut q: Query<&mut Transform, With<BatVisual>>,
+) {
+    let scale = bat_scale(game_time.0);
+
+    // scale the visual and its colliders, keeping the handle in place
+    let visual_scale = scale * rule_effects.bat_scale;
+    for mut transform in q.iter_mut() {
+        transform.scale.y = visual_scale;
+        transform.translation.y = BAT_VISUAL_OFFSET * visual_scale;
+    }
+
+    let milestone = ((1.0 - scale) * 10.0 + 0.001).floor() as u32;
+    if milestone > bat_shrink_milestone.0 {
+        bat_shrink_milestone.0 = milestone;
+        announcement.text = format!("Bat shrunk to {}%", 100 - milestone * 10);
+        announcement.timer = 2.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::SystemState;
+
+    const SIZE: f32 = 0.05;
+    const DT: f32 = 0.1;
+
+    // bat colliders at these positions swinging at these velocities
+    fn bat_with_colliders(swings: &[(Vec3, Vec3)]) -> World {
+        let mut world = World::new();
+        for (index, (position, decaying_vel)) in swings.iter().enumerate() {
+            world.spawn().insert_bundle((
+                BatCollider(index as i32),
+                GlobalTransform::from_translation(*position),
+                HistoricVelocity {
+                    previous_pos: *position,
+                    decaying_vel: *decaying_vel,
+                },
+            ));
+        }
+        world
+    }
+
+    // a held still bat can't bunt
+    fn swing_threshold() -> PhysicsConfig {
+        PhysicsConfig {
+            min_swing_speed: 0.05,
+            ..default()
+        }
+    }
+
+    // one frame of a pitch at ball_pos against the bat, within 0.2 of a collider is in reach
+    fn contact(
+        world: &mut World,
+        physics_config: &PhysicsConfig,
+        ball_pos: Vec3,
+        ball_velocity: Vec3,
+    ) -> Option<(Vec3, Vec3, Vec3)> {
+        let mut state: SystemState<
+            Query<(Entity, &GlobalTransform, &HistoricVelocity), With<BatCollider>>,
+        > = SystemState::new(world);
+        let q_colliders = state.get(world);
+        bat_contact(
+            ball_pos,
+            ball_velocity,
+            0.2,
+            physics_config,
+            DT,
+            &mut ClosestApproach::default(),
+            &q_colliders,
+        )
+    }
+
+    // straight up at 4 m/s off the ground and back down, stepped the way physics steps a ball
+    // returns the apex above the ground, when it got there and when it landed
+    fn throw_up(
+        status: BallStatus,
+        kind: BallKind,
+        gravity_model: GravityModel,
+    ) -> (f32, f32, f32) {
+        let dt = 0.001;
+        let (mut translation, mut velocity) = (vec3(0.0, SIZE, 0.0), vec3(0.0, 4.0, 0.0));
+        let mut flight_time = 0.0;
+        let (mut apex, mut apex_time, mut time) = (0.0, 0.0, 0.0);
+        loop {
+            velocity = apply_gravity(
+                velocity,
+                status,
+                kind,
+                &gravity_model,
+                &mut flight_time,
+                1.0,
+                dt,
+            );
+            (translation, velocity) = step_with_ground(translation, velocity, SIZE, 0.0, 1.0, dt);
+            time += dt;
+
+            if translation.y - SIZE > apex {
+                apex = translation.y - SIZE;
+                apex_time = time;
+            }
+            if translation.y <= SIZE && velocity.y == 0.0 {
+                return (apex, apex_time, time);
+            }
+        }
+    }
+
+    // falling straight down, crosses the ground partway through the step
+    fn drop_onto_ground(restitution: f32) -> (Vec3, Vec3) {
+        step_with_ground(
+            vec3(0.0, SIZE + 0.05, 0.0),
+            vec3(0.0, -2.0, 0.0),
+            SIZE,
+            restitution,
+            1.0,
+            DT,
+        )
+    }
+
+    #[test]
+    fn full_restitution_keeps_vertical_speed() {
+        let (translation, velocity) = drop_onto_ground(1.0);
+        assert!((velocity.y - 2.0).abs() < 1e-5);
+        assert!(translation.y > SIZE);
+    }
+
+    #[test]
+    fn zero_restitution_stops_vertical_motion() {
+        let (translation, velocity) = drop_onto_ground(0.0);
+        assert_eq!(velocity.y, 0.0);
+        assert!((translation.y - SIZE).abs() < 1e-5);
+    }
+
+    #[test]
+    fn fast_falling_ball_bounces_instead_of_tunneling() {
+        // a whole meter and then some below the floor by the end of the step
+        let (translation, velocity) = step_with_ground(
+            vec3(0.0, 1.0, 0.0),
+            vec3(0.0, -200.0, 0.0),
+            SIZE,
+            0.7,
+            0.9,
+            0.5,
+        );
+        assert!(translation.y >= SIZE);
+        assert!(velocity.y > 0.0);
+    }
+
+    #[test]
+    fn ball_through_the_floor_in_one_frame_bounces_at_the_crossing() {
+        // would end the step 0.2 below the ground without the swept check
+        let (translation, velocity) = step_with_ground(
+            vec3(0.0, 0.2, 0.0),
+            vec3(10.0, -4.0, 0.0),
+            SIZE,
+            0.5,
+            1.0,
+            DT,
+        );
+
+        // touches down 37.5% of the way through, then spends the rest bouncing up at 2
+        assert!(translation.distance(vec3(1.0, 0.175, 0.0)) < 1e-5);
+        assert!(velocity.distance(vec3(10.0, 2.0, 0.0)) < 1e-5);
+    }
+
+    #[test]
+    fn wells_pull_nearby_balls_toward_them() {
+        let well = GravityWell {
+            strength: 5.0,
+            radius: 2.0,
+        };
+        let well_position = vec3(0.0, 1.0, 0.0);
+        let pull_at =
+            |position: Vec3| well_acceleration(position, [(well_position, &well)].into_iter());
+
+        let near = vec3(0.5, 1.0, 0.0);
+        let farther = vec3(1.5, 1.0, 0.0);
+        let near_pull = pull_at(near);
+        let farther_pull = pull_at(farther);
+
+        // toward the well, stronger the closer the ball passes
+        assert!(
+            near_pull
+                .normalize()
+                .dot((well_position - near).normalize())
+                > 0.999
+        );
+        assert!(
+            farther_pull
+                .normalize()
+                .dot((well_position - farther).normalize())
+                > 0.999
+        );
+        assert!(near_pull.length() > farther_pull.length());
+
+        // nothing outside the radius
+        assert_eq!(pull_at(vec3(3.0, 1.0, 0.0)), Vec3::ZERO);
+    }
+
+    #[test]
+    fn swing_pull_follows_the_sideways_part_of_the_swing() {
+        let pitch = vec3(-10.0, 0.0, 0.0);
+
+        // square across the pitch, pulled the way the bat goes
+        let pull = swing_pull(pitch, vec3(0.0, 0.0, 1.0));
+        assert!((pull - vec3(0.0, 0.0, SWING_PULL)).length() < 1e-5);
+        let pull = swing_pull(pitch, vec3(0.0, 0.0, -1.0));
+        assert!((pull - vec3(0.0, 0.0, -SWING_PULL)).length() < 1e-5);
+
+        // along the pitch or up and down, no pull at all
+        assert!(swing_pull(pitch, vec3(1.0, 0.0, 0.0)).length() < 1e-5);
+        assert!(swing_pull(pitch, vec3(0.0, 1.0, 0.0)).length() < 1e-5);
+    }
+
+    #[test]
+    fn ground_friction_brings_a_rolling_ball_to_rest() {
+        let config = PhysicsConfig::default();
+        let deceleration = config.ground_friction * GRAVITY;
+        let dt = 1.0 / 60.0;
+
+        // 0.6 * 2 m/s² takes 10 m/s down in a bit over 8 seconds
+        let mut velocity = vec3(6.0, 0.0, 8.0);
+        let mut steps = 0;
+        while vec2(velocity.x, velocity.z).length() >= config.rest_speed {
+            velocity = apply_ground_friction(velocity, deceleration, dt);
+            steps += 1;
+            assert!(steps < 600, "still rolling after {} steps", steps);
+        }
+
+        // and stays stopped rather than reversing
+        let stopped = apply_ground_friction(vec3(0.01, 0.0, 0.0), deceleration, dt);
+        assert_eq!(stopped, Vec3::ZERO);
+    }
+
+    #[test]
+    fn swing_through_a_pitch_sends_it_back_and_across() {
+        let pitch = vec3(-10.0, 0.0, 0.0);
+        let swing = vec3(0.0, 0.0, 0.2);
+        let mut world = bat_with_colliders(&[(Vec3::ZERO, swing)]);
+
+        let (ball_pos, pitch_velocity, decaying_vel) =
+            contact(&mut world, &default(), vec3(0.05, 0.0, 0.0), pitch).expect("no hit");
+        assert_eq!(ball_pos, vec3(0.05, 0.0, 0.0));
+        assert_eq!(pitch_velocity, pitch);
+        assert_eq!(decaying_vel, swing);
+
+        // back at 4 * 0.2 of the pitch speed, across at 15 * 0.2 plus the pull of 10 * 0.2
+        let velocity = hit_velocity(pitch_velocity, decaying_vel, false);
+        assert!((velocity - vec3(8.0, 0.0, 5.0)).length() < 1e-4);
+        let velocity = hit_velocity(pitch_velocity, decaying_vel, true);
+        assert!((velocity - vec3(8.0, 0.0, 5.0) * 1.2).length() < 1e-4);
+    }
+
+    #[test]
+    fn swinging_the_bat_builds_up_hit_velocity() {
+        let mut world = bat_with_colliders(&[(Vec3::ZERO, Vec3::ZERO)]);
+        #[cfg(feature = "debug")]
+        world.insert_resource(StepMode(false));
+        let collider = world
+            .query_filtered::<Entity, With<BatCollider>>()
+            .single(&world);
+        let mut sampling = SystemStage::single(update_collider_historic_velocity);
+
+        // three fixed steps sweeping 0.1 along z each, what the swing adds is decayed every step
+        let step = vec3(0.0, 0.0, 0.1);
+        let mut expected = Vec3::ZERO;
+        for frame in 1..=3 {
+            *world.get_mut::<GlobalTransform>(collider).unwrap() =
+                GlobalTransform::from_translation(step * frame as f32);
+            sampling.run(&mut world);
+            expected = (expected + step) * 0.7;
+        }
+
+        let pitch = vec3(-10.0, 0.0, 0.0);
+        let (_, pitch_velocity, decaying_vel) =
+            contact(&mut world, &default(), vec3(0.05, 0.0, 0.3), pitch).expect("no hit");
+        assert!((decaying_vel - expected).length() < 1e-5);
+
+        // back at 4x and across at 15x plus the pull of 10x the sampled swing
+        let velocity = hit_velocity(pitch_velocity, decaying_vel, false);
+        assert!((velocity - vec3(40.0, 0.0, 25.0) * expected.z).length() < 1e-4);
+
+        // holding the bat still lets the swing die away
+        for _ in 0..10 {
+            sampling.run(&mut world);
+        }
+        let historic = world.get::<HistoricVelocity>(collider).unwrap();
+        assert!((historic.decaying_vel - expected * 0.7f32.powi(10)).length() < 1e-6);
+    }
+
+    #[test]
+    fn angled_bounce_scales_normal_and_tangent_separately() {
+        let config = PhysicsConfig::default();
+        let (_, velocity) = step_with_ground(
+            vec3(0.0, SIZE + 0.05, 0.0),
+            vec3(3.0, -2.0, -4.0),
+            SIZE,
+            config.ground_restitution,
+            config.ground_tangent_restitution,
+            DT,
+        );
+        assert!((velocity.y - 2.0 * config.ground_restitution).abs() < 1e-5);
+        let tangent = vec2(3.0, -4.0) * config.ground_tangent_restitution;
+        assert!((vec2(velocity.x, velocity.z) - tangent).length() < 1e-5);
+    }
+
+    #[test]
+    fn pressure_ball_keeps_speeding_up_while_thrown() {
+        let dt = 1.0 / 60.0;
+        let mut velocity = vec3(-10.0, 1.0, 0.5);
+        let direction = velocity.normalize();
+        for _ in 0..120 {
+            let faster = accelerate(velocity, PRESSURE_BALL_RATE, dt);
+            assert!(faster.length() > velocity.length());
+            velocity = faster;
+        }
+
+        // same line, over three times the speed after two seconds at 0.6
+        assert!((velocity.normalize() - direction).length() < 1e-5);
+        assert!(velocity.length() > vec3(-10.0, 1.0, 0.5).length() * 3.0);
+    }
+
+    #[test]
+    fn stationary_bat_lets_the_pitch_through() {
+        let config = swing_threshold();
+        let mut world = bat_with_colliders(&[(Vec3::ZERO, Vec3::ZERO)]);
+        assert!(contact(
+            &mut world,
+            &config,
+            vec3(0.05, 0.0, 0.0),
+            vec3(-10.0, 0.0, 0.0)
+        )
+        .is_none());
+
+        // just under min_swing_speed is still too slow
+        let slow = config.min_swing_speed * 0.9;
+        let mut world = bat_with_colliders(&[(Vec3::ZERO, vec3(0.0, 0.0, slow))]);
+        assert!(contact(
+            &mut world,
+            &config,
+            vec3(0.05, 0.0, 0.0),
+            vec3(-10.0, 0.0, 0.0)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn stationary_bat_hits_without_a_swing_threshold() {
+        let mut world = bat_with_colliders(&[(Vec3::ZERO, Vec3::ZERO)]);
+        assert!(contact(
+            &mut world,
+            &default(),
+            vec3(0.05, 0.0, 0.0),
+            vec3(-10.0, 0.0, 0.0)
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn ball_glances_off_a_45_degree_board() {
+        let normal = vec3(1.0, 0.0, 1.0).normalize();
+        let board_transform = Transform::from_rotation(Quat::from_rotation_arc(Vec3::Z, normal));
+        let mut board = Backboard {
+            normal,
+            half_extents: Vec2::ONE,
+            restitution: 1.0,
+        };
+
+        // straight in along -z, out along +x
+        let velocity = vec3(0.0, 0.0, -10.0);
+        let translation = vec3(0.0, 0.0, 0.5);
+        let (_, out) = bounce_off_backboard(
+            translation,
+            translation + velocity * DT,
+            velocity,
+            SIZE,
+            &board_transform,
+            &board,
+        )
+        .expect("missed the board");
+        assert!((-velocity).dot(out).abs() < 1e-4);
+        assert!((out.length() - board.restitution * velocity.length()).abs() < 1e-4);
+
+        // a softer board only takes speed out of the normal
+        board.restitution = 0.5;
+        let out = reflect(velocity, board.normal, board.restitution);
+        assert!((out.dot(normal) + 0.5 * velocity.dot(normal)).abs() < 1e-4);
+        let along_board = |v: Vec3| v - v.dot(normal) * normal;
+        assert!((along_board(out) - along_board(velocity)).length() < 1e-4);
+    }
+
+    #[test]
+    fn nearest_swinging_collider_hits() {
+        // the far collider comes first and swings harder
+        let mut world = bat_with_colliders(&[
+            (vec3(0.0, 0.0, 0.15), vec3(0.0, 0.0, 0.5)),
+            (vec3(0.0, 0.0, 0.02), vec3(0.0, 0.0, 0.2)),
+            (Vec3::ZERO, Vec3::ZERO),
+        ]);
+        let (.., decaying_vel) = contact(
+            &mut world,
+            &swing_threshold(),
+            Vec3::ZERO,
+            vec3(-10.0, 0.0, 0.0),
+        )
+        .expect("no hit");
+        assert_eq!(decaying_vel, vec3(0.0, 0.0, 0.2));
+    }
+
+    #[test]
+    fn gravity_models_set_apex_and_hang_time() {
+        let hit = |gravity_model| throw_up(BallStatus::Hit, BallKind::Standard, gravity_model);
+
+        // v²/2g and 2v/g
+        let (apex, _, hang_time) = hit(GravityModel::Constant);
+        assert!((apex - 4.0).abs() < 0.01);
+        assert!((hang_time - 4.0).abs() < 0.01);
+
+        // half gravity the whole way goes twice as high for twice as long
+        let (apex, _, hang_time) = hit(GravityModel::HangTime {
+            start: 0.5,
+            end: 0.5,
+            ramp: 1.0,
+        });
+        assert!((apex - 8.0).abs() < 0.02);
+        assert!((hang_time - 8.0).abs() < 0.02);
+
+        // light on the way up, heavy on the way down
+        let ramped = GravityModel::HangTime {
+            start: 0.5,
+            end: 2.0,
+            ramp: 1.0,
+        };
+        let (_, apex_time, hang_time) = hit(ramped);
+        assert!(apex_time > hang_time - apex_time);
+
+        // pitches keep constant gravity whatever the model
+        let (apex, _, hang_time) = throw_up(BallStatus::Thrown, BallKind::Standard, ramped);
+        assert!((apex - 4.0).abs() < 0.01);
+        assert!((hang_time - 4.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn balloons_hang_longer_than_stones() {
+        let hang_time = |kind| throw_up(BallStatus::Hit, kind, GravityModel::Constant).2;
+        let (balloon, standard, stone) = (
+            hang_time(BallKind::Balloon),
+            hang_time(BallKind::Standard),
+            hang_time(BallKind::Stone),
+        );
+        assert!(balloon > standard);
+        assert!(standard > stone);
+
+        // 2v/g with each kind's share of gravity
+        assert!((balloon - 4.0 / BallKind::Balloon.gravity_scale()).abs() < 0.05);
+        assert!((stone - 4.0 / BallKind::Stone.gravity_scale()).abs() < 0.02);
+    }
+}
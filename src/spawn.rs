@@ -0,0 +1,879 @@
+use crate::{components::*, config::load_config, game::fixed_step, physics::pitch_velocity};
+use bevy::{asset::Asset, math::vec3, prelude::*, utils::HashMap};
+use rand::Rng;
+use std::path::Path;
+
+// the field, the player and every pitch
+pub(crate) struct SpawnPlugin;
+
+impl Plugin for SpawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BallSize {
+            min: 0.035,
+            max: 0.065,
+        })
+        .insert_resource(PitchConfig {
+            origins: vec![
+                // straight
+                PitchOrigin {
+                    position: vec3(-2.5, 0.5, -2.5),
+                    flight_time: 1.35,
+                },
+                // left
+                PitchOrigin {
+                    position: vec3(-3.0, 0.5, -1.0),
+                    flight_time: 1.35,
+                },
+                // right
+                PitchOrigin {
+                    position: vec3(-1.0, 0.5, -3.0),
+                    flight_time: 1.35,
+                },
+                // high
+                PitchOrigin {
+                    position: vec3(-2.5, 1.8, -2.5),
+                    flight_time: 1.2,
+                },
+                // low
+                PitchOrigin {
+                    position: vec3(-2.5, 0.15, -2.5),
+                    flight_time: 1.5,
+                },
+            ],
+            strike_point: vec3(4.3, 1.13, 4.3),
+            wild_pitch_chance: 0.08,
+        })
+        .insert_resource(PitchDeck::default())
+        .insert_resource(NextPitch::default())
+        .insert_resource(CoOp(false))
+        .insert_resource(load_config::<StrikeZone>("ld51_strike_zone").unwrap_or_default())
+        .add_startup_system(setup)
+        .add_system(apply_co_op)
+        .add_system(animate_ball_spawn)
+        .add_system(animate_ball_despawn)
+        .add_system_set(SystemSet::on_update(AppState::InGame).with_system(clear_resting_balls))
+        .add_system_set(
+            // throw ball every x seconds
+            SystemSet::on_update(AppState::InGame)
+                .with_run_criteria(fixed_step("pitch", PITCH_TIME))
+                .with_system(throw_ball),
+        );
+    }
+}
+
+// for assets that aren't bundled, skipped rather than logging a load error when missing
+// browsers can't check for a file without fetching it, so the web build goes without
+fn load_if_present<T: Asset>(asset_server: &AssetServer, path: &str) -> Option<Handle<T>> {
+    if cfg!(target_family = "wasm") || !asset_server.asset_io().is_file(Path::new(path)) {
+        return None;
+    }
+    Some(asset_server.load(path))
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    strike_zone: Res<StrikeZone>,
+    pitch_config: Res<PitchConfig>,
+) {
+    // load hit sound
+    let hit_sound: Handle<AudioSource> = asset_server.load("hit.ogg");
+    commands.insert_resource(HitSound(hit_sound));
+
+    // stadium ambience, not bundled, drop a loop in assets to enable it
+    commands.insert_resource(AmbienceController {
+        source: load_if_present(&asset_server, "ambience.ogg"),
+        sink: None,
+        swell: 0.0,
+    });
+
+    // menu loop, not bundled either
+    commands.insert_resource(MusicController {
+        menu: load_if_present(&asset_server, "music_menu.ogg"),
+        menu_sink: None,
+        menu_level: 0.0,
+        game_level: 0.0,
+    });
+
+    // music stems, not bundled either, layered in by combo, missing ones are left out
+    commands.insert_resource(MusicIntensity {
+        stems: MUSIC_STEMS
+            .iter()
+            .filter_map(|(path, combo)| {
+                Some(MusicStem {
+                    source: load_if_present(&asset_server, path)?,
+                    sink: None,
+                    combo: *combo,
+                    volume: 0.0,
+                })
+            })
+            .collect(),
+        started: false,
+    });
+
+    // init ball assets
+    let ball_assets = BallAssets {
+        mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 1.0,
+            subdivisions: 4,
+        })),
+        material: materials.add(Color::WHITE.into()),
+        custom_mesh: load_config::<BallConfig>("ld51_ball")
+            .unwrap_or_default()
+            .mesh_path
+            .map(|path| asset_server.load(&format!("{}#Mesh0/Primitive0", path))),
+        custom_scale: None,
+    };
+    commands.insert_resource(ball_assets);
+    commands.insert_resource(PitchPalette {
+        fastball: materials.add(Color::RED.into()),
+        curveball: materials.add(Color::BLUE.into()),
+        changeup: materials.add(Color::YELLOW.into()),
+        hazard: materials.add(StandardMaterial {
+            base_color: Color::BLACK,
+            emissive: Color::RED,
+            ..default()
+        }),
+        pressure: materials.add(StandardMaterial {
+            base_color: Color::ORANGE,
+            emissive: Color::ORANGE,
+            ..default()
+        }),
+        balloon: materials.add(Color::PINK.into()),
+        stone: materials.add(Color::DARK_GRAY.into()),
+    });
+
+    // init particle assets
+    commands.insert_resource(ParticleAssets {
+        mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 1.0,
+            subdivisions: 1,
+        })),
+        materials: HashMap::default(),
+    });
+
+    // ground plane
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Plane { size: 10.0 })),
+            material: materials.add(Color::GREEN.into()),
+            ..default()
+        })
+        .insert(Ground);
+
+    // targets
+    let target_assets = TargetAssets {
+        idle: materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 1.0, 1.0, 0.4),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+        active: materials.add(StandardMaterial {
+            base_color: Color::rgba(1.0, 0.9, 0.1, 0.7),
+            emissive: Color::rgb(0.6, 0.5, 0.0),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+    };
+    let target_mesh = meshes.add(Mesh::from(shape::Icosphere {
+        radius: TARGET_RADIUS,
+        subdivisions: 3,
+    }));
+    for (i, position) in [
+        vec3(-2.0, 1.0, 1.0),
+        vec3(-2.0, 1.5, -2.0),
+        vec3(1.0, 1.0, -2.0),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: target_mesh.clone(),
+                material: target_assets.idle.clone(),
+                transform: Transform::from_translation(position),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(Target(i));
+    }
+    commands.insert_resource(target_assets);
+
+    // gravity wells between the plate and the targets, hidden until enabled
+    let well_mesh = meshes.add(Mesh::from(shape::Icosphere {
+        radius: 0.15,
+        subdivisions: 2,
+    }));
+    let well_material = materials.add(StandardMaterial {
+        base_color: Color::BLACK,
+        emissive: Color::PURPLE,
+        ..default()
+    });
+    for position in [vec3(1.0, 1.0, 1.5), vec3(1.5, 1.3, -0.5)] {
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: well_mesh.clone(),
+                material: well_material.clone(),
+                transform: Transform::from_translation(position),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(GravityWell {
+                strength: 6.0,
+                radius: 1.2,
+            });
+    }
+
+    // backboard past the plate on the right, where wild pitches that side end up,
+    // angled to send them back across the plate, hidden until enabled
+    let pitch_direction = vec3(1.0, 0.0, 1.0).normalize();
+    let right = Vec3::Y.cross(pitch_direction);
+    let backboard_pos = pitch_config.strike_point + right * WILD_PITCH_MISS + pitch_direction * 0.4;
+    let rebound = (pitch_config.strike_point - backboard_pos).normalize();
+    let normal = (rebound - pitch_direction).normalize();
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(1.0, 1.2, 0.03))),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.6, 0.8, 1.0, 0.35),
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            transform: Transform::from_translation(backboard_pos)
+                .with_rotation(Quat::from_rotation_arc(Vec3::Z, normal)),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(Backboard {
+            normal,
+            half_extents: Vec2::new(0.5, 0.6),
+            restitution: 0.6,
+        });
+
+    // scoreboard, facing the player
+    let scoreboard_pos = vec3(-4.5, 2.0, -4.5);
+    let digit_width = 0.4;
+    let digit_height = 0.8;
+    let thickness = 0.06;
+    let horizontal_segment = meshes.add(Mesh::from(shape::Box::new(
+        digit_width,
+        thickness,
+        thickness,
+    )));
+    let vertical_segment = meshes.add(Mesh::from(shape::Box::new(
+        thickness,
+        digit_height / 2.0,
+        thickness,
+    )));
+    let segment_material = materials.add(StandardMaterial {
+        base_color: Color::YELLOW,
+        unlit: true,
+        ..default()
+    });
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(
+                SCOREBOARD_DIGITS as f32 * 0.6 + 0.2,
+                digit_height + 0.4,
+                0.05,
+            ))),
+            material: materials.add(Color::rgb(0.1, 0.1, 0.1).into()),
+            transform: Transform::from_translation(scoreboard_pos)
+                .looking_at(scoreboard_pos * 2.0 - vec3(5.0, 2.0, 5.0), Vec3::Y),
+            ..default()
+        })
+        .with_children(|parent| {
+            for digit in 0..SCOREBOARD_DIGITS {
+                let x = (SCOREBOARD_DIGITS as f32 - 1.0) * 0.3 - digit as f32 * 0.6;
+                let segments = [
+                    (0.0, digit_height / 2.0, &horizontal_segment),
+                    (digit_width / 2.0, digit_height / 4.0, &vertical_segment),
+                    (digit_width / 2.0, -digit_height / 4.0, &vertical_segment),
+                    (0.0, -digit_height / 2.0, &horizontal_segment),
+                    (-digit_width / 2.0, -digit_height / 4.0, &vertical_segment),
+                    (-digit_width / 2.0, digit_height / 4.0, &vertical_segment),
+                    (0.0, 0.0, &horizontal_segment),
+                ];
+                for (segment, (sx, sy, mesh)) in segments.into_iter().enumerate() {
+                    parent
+                        .spawn_bundle(PbrBundle {
+                            mesh: mesh.clone(),
+                            material: segment_material.clone(),
+                            transform: Transform::from_xyz(x + sx, sy, 0.05),
+                            ..default()
+                        })
+                        .insert(ScoreSegment { digit, segment });
+                }
+            }
+        });
+
+    // wind flag, pole with a cloth hanging off a pivot at the top
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(0.05, 3.0, 0.05))),
+            material: materials.add(Color::GRAY.into()),
+            transform: Transform::from_xyz(-4.5, 1.5, 1.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(SpatialBundle {
+                    transform: Transform::from_xyz(0.0, 1.3, 0.0),
+                    ..default()
+                })
+                .insert(WindFlag)
+                .with_children(|parent| {
+                    parent.spawn_bundle(PbrBundle {
+                        mesh: meshes.add(Mesh::from(shape::Box::new(0.6, 0.35, 0.01))),
+                        material: materials.add(StandardMaterial {
+                            base_color: Color::ORANGE,
+                            cull_mode: None,
+                            ..default()
+                        }),
+                        transform: Transform::from_xyz(0.3, 0.0, 0.0),
+                        ..default()
+                    });
+                });
+        });
+
+    // spawn warning, faded out until a pitch is close
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Icosphere {
+                radius: 0.25,
+                subdivisions: 2,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(1.0, 0.8, 0.2, 0.0),
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(SpawnWarningMarker);
+
+    // next ball highlight, a see-through shell around the ball
+    commands
+        .spawn_bundle(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Icosphere {
+                radius: 1.0,
+                subdivisions: 2,
+            })),
+            material: materials.add(StandardMaterial {
+                base_color: Color::rgba(0.3, 1.0, 0.9, 0.35),
+                unlit: true,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            }),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(NextBallMarker);
+
+    // strike zone, thin boxes along the twelve edges
+    let edge_material = materials.add(StandardMaterial {
+        base_color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    let bounds = strike_zone.bounds;
+    commands
+        .spawn_bundle(SpatialBundle {
+            transform: Transform::from_translation(strike_zone.center)
+                .with_rotation(strike_zone.rotation()),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(StrikeZoneMarker)
+        .with_children(|parent| {
+            for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+                let size = axis * bounds * 2.0 + Vec3::splat(0.01);
+                let mesh = meshes.add(Mesh::from(shape::Box::new(size.x, size.y, size.z)));
+                // the four edges along this axis sit at the corners of the other two
+                let across = (Vec3::ONE - axis) * bounds;
+                for (a, b) in [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)] {
+                    let offset = if axis == Vec3::X {
+                        vec3(0.0, a * across.y, b * across.z)
+                    } else if axis == Vec3::Y {
+                        vec3(a * across.x, 0.0, b * across.z)
+                    } else {
+                        vec3(a * across.x, b * across.y, 0.0)
+                    };
+                    parent.spawn_bundle(PbrBundle {
+                        mesh: mesh.clone(),
+                        material: edge_material.clone(),
+                        transform: Transform::from_translation(offset),
+                        ..default()
+                    });
+                }
+            }
+        });
+
+    // curveball path, faint dots placed by update_curve_arc
+    let curve_arc_mesh = meshes.add(Mesh::from(shape::Icosphere {
+        radius: 0.02,
+        subdivisions: 1,
+    }));
+    let curve_arc_material = materials.add(StandardMaterial {
+        base_color: Color::rgba(1.0, 1.0, 1.0, 0.4),
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    for i in 0..CURVE_ARC_DOTS {
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: curve_arc_mesh.clone(),
+                material: curve_arc_material.clone(),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(CurveArcDot(i));
+    }
+
+    // swing preview, placed by update_swing_arc
+    let swing_arc_material = materials.add(StandardMaterial {
+        base_color: Color::rgba(1.0, 0.9, 0.5, 0.3),
+        unlit: true,
+        alpha_mode: AlphaMode::Blend,
+        ..default()
+    });
+    for i in 0..SWING_ARC_DOTS {
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: curve_arc_mesh.clone(),
+                material: swing_arc_material.clone(),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(SwingArcDot(i));
+    }
+
+    // fielders
+    let fielder_mesh = meshes.add(Mesh::from(shape::Capsule {
+        radius: 0.15,
+        depth: 0.5,
+        ..default()
+    }));
+    let fielder_material = materials.add(Color::ORANGE_RED.into());
+    for home in [
+        vec3(-3.0, 0.4, -1.0),
+        vec3(-2.5, 0.4, -2.5),
+        vec3(-1.0, 0.4, -3.0),
+    ] {
+        commands
+            .spawn_bundle(PbrBundle {
+                mesh: fielder_mesh.clone(),
+                material: fielder_material.clone(),
+                transform: Transform::from_translation(home),
+                ..default()
+            })
+            .insert(Fielder { speed: 1.5, home });
+    }
+
+    // light
+    commands.spawn_bundle(PointLightBundle {
+        point_light: PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+
+    // camera fov to return to after punches
+    commands.insert_resource(BaseFov(PerspectiveProjection::default().fov));
+
+    // spawn player
+    let bat_visual_config = load_config::<BatVisualConfig>("ld51_bat_visual").unwrap_or_default();
+    commands
+        .spawn_bundle(SpatialBundle {
+            transform: Transform::from_xyz(5.0, 1.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        })
+        .with_children(|parent| {
+            // camera
+            parent.spawn_bundle(Camera3dBundle { ..default() });
+
+            // bat
+            spawn_bat(
+                parent,
+                BatOwner(0),
+                Vec3::ZERO,
+                &bat_visual_config,
+                &mut meshes,
+                &mut materials,
+                &asset_server,
+            );
+        });
+
+    // bat collision points, spawned by rebuild_bat_colliders
+    commands.insert_resource(BatColliderAssets {
+        anchor_mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 0.12,
+            subdivisions: 1,
+        })),
+        anchor_material: materials.add(Color::PURPLE.into()),
+        segment_mesh: meshes.add(Mesh::from(shape::Icosphere {
+            radius: 0.1,
+            subdivisions: 2,
+        })),
+        segment_material: materials.add(Color::WHITE.into()),
+    });
+}
+
+// a bat in player space, `offset` from where the first bat stands
+fn spawn_bat(
+    parent: &mut ChildBuilder,
+    owner: BatOwner,
+    offset: Vec3,
+    bat_visual_config: &BatVisualConfig,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    asset_server: &AssetServer,
+) {
+    parent
+        .spawn_bundle((
+            Bat,
+            owner,
+            Transform::from_translation(vec3(0.0, 0.0, -1.0) + offset),
+            Visibility::default(),
+            ComputedVisibility::default(),
+            GlobalTransform::default(),
+        ))
+        .with_children(|parent| {
+            // bat visual
+            parent
+                .spawn_bundle(SpatialBundle {
+                    transform: Transform::from_xyz(0.0, BAT_VISUAL_OFFSET, 0.0),
+                    ..default()
+                })
+                .insert(BatVisual)
+                .insert(owner)
+                .with_children(|parent| {
+                    for part in bat_visual_config.parts.iter() {
+                        let mesh = match &part.shape {
+                            BatPartShape::Capsule { radius, depth } => {
+                                meshes.add(Mesh::from(shape::Capsule {
+                                    radius: *radius,
+                                    rings: 4,
+                                    depth: *depth,
+                                    latitudes: 4,
+                                    longitudes: 4,
+                                    ..default()
+                                }))
+                            }
+                            BatPartShape::Box { size } => {
+                                meshes.add(Mesh::from(shape::Box::new(size.x, size.y, size.z)))
+                            }
+                            BatPartShape::Sphere { radius } => {
+                                meshes.add(Mesh::from(shape::Icosphere {
+                                    radius: *radius,
+                                    subdivisions: 2,
+                                }))
+                            }
+                            BatPartShape::Mesh { path } => {
+                                asset_server.load(&format!("{}#Mesh0/Primitive0", path))
+                            }
+                        };
+                        parent
+                            .spawn_bundle(PbrBundle {
+                                mesh,
+                                material: materials.add(part.color.into()),
+                                transform: Transform::from_translation(part.offset),
+                                ..default()
+                            })
+                            .insert(BatVisualPart);
+                    }
+                });
+        });
+}
+
+// second bat for the gamepad player, next to the first
+fn apply_co_op(
+    mut commands: Commands,
+    co_op: Res<CoOp>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    q_bats: Query<(Entity, &BatOwner, &Parent), With<Bat>>,
+    q_segments: Query<(Entity, &BatOwner), With<BatSegment>>,
+) {
+    if !co_op.is_changed() {
+        return;
+    }
+
+    let player = match q_bats.iter().find(|(_, owner, _)| owner.0 == 0) {
+        Some((_, _, parent)) => **parent,
+        None => return,
+    };
+    let second_bat = q_bats.iter().find(|(_, owner, _)| owner.0 == 1);
+
+    match (co_op.0, second_bat) {
+        (true, None) => {
+            // rebuild_bat_colliders picks the new bat up once it's placed
+            let bat_visual_config =
+                load_config::<BatVisualConfig>("ld51_bat_visual").unwrap_or_default();
+            commands.entity(player).with_children(|parent| {
+                spawn_bat(
+                    parent,
+                    BatOwner(1),
+                    vec3(CO_OP_BAT_OFFSET, 0.0, 0.0),
+                    &bat_visual_config,
+                    &mut meshes,
+                    &mut materials,
+                    &asset_server,
+                );
+            });
+        }
+        (false, Some((bat, ..))) => {
+            // segments live in world space, outside the bat
+            commands.entity(bat).despawn_recursive();
+            for (segment, owner) in q_segments.iter() {
+                if owner.0 == 1 {
+                    commands.entity(segment).despawn();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn throw_ball(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    fielding_state: Res<State<FieldingState>>,
+    ball_assets: Res<BallAssets>,
+    ball_size: Res<BallSize>,
+    (pitch_config, pitch_palette, no_tells, aim_assist): (
+        Res<PitchConfig>,
+        Res<PitchPalette>,
+        Res<NoTells>,
+        Res<AimAssist>,
+    ),
+    camera_intro: Res<CameraIntro>,
+    rule_effects: Res<RuleEffects>,
+    pitch_deck: Res<PitchDeck>,
+    (match_length, mut pitch_count, mut inning): (
+        Res<MatchLength>,
+        ResMut<PitchCount>,
+        ResMut<Inning>,
+    ),
+    mut next_pitch: ResMut<NextPitch>,
+    mut rng: ResMut<GameRng>,
+) {
+    if matches!(
+        app_state.current(),
+        AppState::GameOver | AppState::InningBreak | AppState::PhotoMode | AppState::Settings
+    ) || camera_intro.active
+        || *fielding_state.current() == FieldingState::Aiming
+        || inning.pitches_left == 0
+        || match_length.finished(&pitch_count)
+    {
+        return;
+    }
+    inning.pitches_left -= 1;
+    pitch_count.0 += 1;
+
+    // multi-ball rules throw extra pitches at once
+    for _ in 0..=rule_effects.extra_balls {
+        // the first ball comes from the telegraphed origin, extra balls are a surprise
+        let origin = match next_pitch.origin.take() {
+            Some(origin) => origin,
+            None => rng.0.gen_range(0..pitch_config.origins.len()),
+        };
+        let origin = &pitch_config.origins[origin.min(pitch_config.origins.len() - 1)];
+        let pitch_type = match next_pitch.pitch_type.take() {
+            Some(pitch_type) => pitch_type,
+            None => pitch_deck.sample(&mut rng.0),
+        };
+        let wild = std::mem::take(&mut next_pitch.wild);
+
+        let flight_time =
+            pitch_flight_time(origin, pitch_type, &rule_effects, &inning, &aim_assist);
+        let material = match pitch_type {
+            PitchType::Fastball => &pitch_palette.fastball,
+            PitchType::Curveball => &pitch_palette.curveball,
+            PitchType::Changeup => &pitch_palette.changeup,
+        };
+        let hazard = rng.0.gen::<f32>() < HAZARD_CHANCE;
+        let pressure = !hazard && !wild && rng.0.gen::<f32>() < PRESSURE_BALL_CHANCE;
+        let kind = match rng.0.gen::<f32>() {
+            _ if hazard || pressure => BallKind::Standard,
+            roll if roll < BALLOON_CHANCE => BallKind::Balloon,
+            roll if roll < BALLOON_CHANCE + STONE_CHANCE => BallKind::Stone,
+            _ => BallKind::Standard,
+        };
+        let material = if hazard {
+            // bombs are always telegraphed
+            &pitch_palette.hazard
+        } else if pressure {
+            &pitch_palette.pressure
+        } else if kind == BallKind::Balloon {
+            // so are the odd kinds, their flight is too strange to read cold
+            &pitch_palette.balloon
+        } else if kind == BallKind::Stone {
+            &pitch_palette.stone
+        } else if no_tells.0 {
+            &ball_assets.material
+        } else {
+            material
+        };
+
+        // curveballs break to the batter's side
+        let curve = match pitch_type {
+            PitchType::Curveball => {
+                let direction = (pitch_config.strike_point - origin.position).normalize();
+                direction.cross(Vec3::Y).normalize() * CURVE_BREAK
+            }
+            _ => Vec3::ZERO,
+        };
+
+        // wild pitches go wide to either side, at a random height
+        let target = if wild {
+            let direction = (pitch_config.strike_point - origin.position).normalize();
+            let side = if rng.0.gen() { 1.0 } else { -1.0 };
+            pitch_config.strike_point
+                + direction.cross(Vec3::Y).normalize() * side * WILD_PITCH_MISS
+                + Vec3::Y * rng.0.gen_range(-0.3..0.5)
+        } else {
+            pitch_config.strike_point
+        };
+
+        let velocity = pitch_velocity(
+            origin.position,
+            target,
+            flight_time,
+            curve,
+            GRAVITY * rule_effects.gravity_scale * kind.gravity_scale(),
+        );
+
+        let radius = ball_size.min + rng.0.gen::<f32>() * (ball_size.max - ball_size.min);
+        let (mesh, mesh_scale) = match (&ball_assets.custom_mesh, ball_assets.custom_scale) {
+            (Some(custom_mesh), Some(custom_scale)) => (custom_mesh, custom_scale),
+            _ => (&ball_assets.mesh, 1.0),
+        };
+        let scale = Vec3::splat(radius * mesh_scale);
+        let mut ball = commands.spawn_bundle(BallBundle {
+            mesh: mesh.clone_weak(),
+            material: material.clone_weak(),
+            transform: Transform::from_translation(origin.position).with_scale(scale * 0.01),
+            size: Size(radius),
+            velocity: Velocity(velocity),
+            pitch_type,
+            kind,
+            pitch_speed: PitchSpeed(velocity.length()),
+            curve: Curve(curve),
+            ..default()
+        });
+        ball.insert(SpawnAnim { timer: 0.0, scale });
+
+        if hazard {
+            ball.insert(Hazard);
+        }
+        if wild {
+            ball.insert(WildPitch);
+        }
+        if pressure {
+            ball.insert(Accelerating {
+                rate: PRESSURE_BALL_RATE,
+            });
+        }
+    }
+
+    next_pitch.origin = Some(rng.0.gen_range(0..pitch_config.origins.len()));
+    next_pitch.pitch_type = Some(pitch_deck.sample(&mut rng.0));
+    next_pitch.wild = rng.0.gen::<f32>() < pitch_config.wild_pitch_chance;
+}
+
+pub(crate) fn pitch_flight_time(
+    origin: &PitchOrigin,
+    pitch_type: PitchType,
+    rule_effects: &RuleEffects,
+    inning: &Inning,
+    aim_assist: &AimAssist,
+) -> f32 {
+    let flight_time = match pitch_type {
+        PitchType::Fastball => origin.flight_time * 0.75,
+        PitchType::Curveball => origin.flight_time * 1.1,
+        PitchType::Changeup => origin.flight_time * 1.3,
+    };
+    let inning_speedup = 1.0 + (inning.number - 1) as f32 * INNING_SPEEDUP;
+    flight_time / rule_effects.pitch_speed.max(0.1) / inning_speedup
+        * (1.0 + (aim_assist.0 - 1.0) * 0.5)
+}
+
+// 0.0 for a slow straight pitch up to 1.0 for a fast breaking one
+pub(crate) fn pitch_difficulty(speed: f32, pitch_type: PitchType) -> f32 {
+    let spin = match pitch_type {
+        PitchType::Curveball => CURVEBALL_DIFFICULTY,
+        _ => 0.0,
+    };
+    ((speed - EASY_PITCH_SPEED) / (HARD_PITCH_SPEED - EASY_PITCH_SPEED) + spin).clamp(0.0, 1.0)
+}
+
+fn animate_ball_spawn(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut SpawnAnim, &mut Transform)>,
+) {
+    for (entity, mut spawn_anim, mut transform) in q.iter_mut() {
+        spawn_anim.timer += time.delta_seconds();
+        let t = (spawn_anim.timer / BALL_SPAWN_TIME).min(1.0);
+
+        // ease out, never quite zero so the transform stays invertible
+        transform.scale = spawn_anim.scale * (1.0 - (1.0 - t) * (1.0 - t)).max(0.01);
+        if t >= 1.0 {
+            commands.entity(entity).remove::<SpawnAnim>();
+        }
+    }
+}
+
+// dead balls shrink away instead of piling up on the field
+fn clear_resting_balls(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    physics_config: Res<PhysicsConfig>,
+    mut q: Query<(Entity, &Transform, &Velocity, &Size, &mut RestTimer), With<Status>>,
+) {
+    let dt = time.delta_seconds() * time_scale.simulation();
+    for (entity, transform, velocity, size, mut rest_timer) in q.iter_mut() {
+        let resting = transform.translation.y <= size.0 + GROUND_CONTACT_MARGIN
+            && velocity.0.length() < physics_config.rest_speed;
+        rest_timer.0 = if resting { rest_timer.0 + dt } else { 0.0 };
+
+        if rest_timer.0 > physics_config.rest_time {
+            commands
+                .entity(entity)
+                .remove::<Status>()
+                .remove::<SpawnAnim>()
+                .insert(DespawnAnim {
+                    timer: 0.0,
+                    scale: transform.scale,
+                });
+        }
+    }
+}
+
+fn animate_ball_despawn(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut DespawnAnim, &mut Transform)>,
+) {
+    for (entity, mut despawn_anim, mut transform) in q.iter_mut() {
+        despawn_anim.timer += time.delta_seconds();
+        let t = (despawn_anim.timer / BALL_DESPAWN_TIME).min(1.0);
+
+        transform.scale = despawn_anim.scale * (1.0 - t * t).max(0.01);
+        if t >= 1.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
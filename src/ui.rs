@@ -0,0 +1,1360 @@
+use crate::{
+    components::*,
+    config::{load_config, save_config, unix_time},
+};
+use bevy::{
+    math::vec2,
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+    },
+    window::{PresentMode, WindowId, WindowResized},
+};
+use std::time::{Duration, Instant};
+
+// hud text, panels and the scoreboard
+pub(crate) struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ShowHeatmap(false))
+            .insert_resource(load_config::<HudSettings>("ld51_hud").unwrap_or_default())
+            .insert_resource(load_config("ld51_vsync").unwrap_or(Vsync(true)))
+            .insert_resource(load_config::<FrameCap>("ld51_frame_cap").unwrap_or_default())
+            .insert_resource(load_config("ld51_render_scale").unwrap_or(RenderScale(1.0)))
+            .insert_resource(FrameTimes::default())
+            .insert_resource(ShowFrameGraph(false))
+            .insert_resource(SwingPeak::default())
+            .add_startup_system(setup_hud)
+            .add_system(rescale_hud)
+            .add_system_set(
+                SystemSet::on_enter(AppState::InningBreak).with_system(show_inning_summary),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::InningBreak).with_system(hide_inning_summary),
+            )
+            .add_system(toggle_heatmap)
+            .add_system(update_heatmap_display)
+            .add_system(apply_vsync)
+            .add_system(apply_render_scale)
+            .add_system(show_ui_on_window_camera.after(apply_render_scale))
+            .add_system_to_stage(CoreStage::Last, limit_frame_rate)
+            .add_system(toggle_frame_graph)
+            .add_system(update_frame_graph)
+            .add_system(update_swing_meter)
+            .add_system(update_combo_bar)
+            .add_system(update_out_icons)
+            .add_system(update_scoreboard)
+            .add_system(update_radar_text)
+            .add_system(update_wind_text)
+            .add_system(update_match_text)
+            .add_system(update_announcement)
+            .add_system(update_game_over_text)
+            .add_system_set(
+                SystemSet::on_enter(AppState::Settings).with_system(spawn_settings_menu),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::Settings).with_system(update_settings_menu),
+            )
+            .add_system_set(SystemSet::on_exit(AppState::Settings).with_system(close_settings_menu))
+            .add_system(update_share_code_text)
+            .add_system(update_pitch_deck_text)
+            .add_system(toggle_help)
+            .add_system(update_help_text)
+            .add_system(update_target_sequence_display);
+    }
+}
+
+fn update_pitch_deck_text(
+    pitch_deck: Res<PitchDeck>,
+    mut q: Query<&mut Text, With<PitchDeckText>>,
+) {
+    if !pitch_deck.is_changed() {
+        return;
+    }
+
+    let mut value = format!("deck: {}", pitch_deck.name);
+    for pitch_type in PitchType::ALL {
+        let weight = pitch_deck
+            .pitches
+            .iter()
+            .find(|(deck_type, _)| *deck_type == pitch_type)
+            .map_or(0, |(_, weight)| *weight);
+        value += &format!(
+            "\n{} ({}) x{}",
+            pitch_type.name(),
+            pitch_type.difficulty(),
+            weight
+        );
+    }
+    for mut text in q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn setup_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    rules: Res<Rules>,
+    mut hud_settings: ResMut<HudSettings>,
+) {
+    hud_settings.font = asset_server.load("fonts/DejaVuSans-Bold.ttf");
+    let hud_settings = &*hud_settings;
+
+    // radar gun
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", hud_settings.text(24.0, Color::rgb(1.0, 0.3, 0.2)))
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        bottom: hud_settings.px(10.0),
+                        right: hud_settings.px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                }),
+        )
+        .insert(RadarText);
+
+    // wind direction relative to the view
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", hud_settings.text(24.0, Color::WHITE)).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(40.0),
+                    right: hud_settings.px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(WindText);
+
+    // current pitch deck and its weights
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", hud_settings.text(16.0, Color::WHITE)).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(70.0),
+                    right: hud_settings.px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(PitchDeckText);
+
+    // share code entry
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(120.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    hud_settings.text(20.0, Color::WHITE),
+                ))
+                .insert(ShareCodeText);
+        });
+
+    // between innings, filled in by show_inning_summary
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                display: Display::None,
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(35.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(InningSummaryPanel)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(
+                    TextBundle::from_section("", hud_settings.text(32.0, Color::WHITE))
+                        .with_text_alignment(TextAlignment::CENTER),
+                )
+                .insert(InningSummaryText);
+        });
+
+    // hit heatmap, tip at the top
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: hud_settings.px(50.0),
+                    left: hud_settings.px(10.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(hud_settings.px(4.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            ..default()
+        })
+        .insert(HeatmapPanel)
+        .with_children(|parent| {
+            for row in 0..HEATMAP_ROWS {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        },
+                        color: Color::NONE.into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for col in 0..HEATMAP_COLS {
+                            parent
+                                .spawn_bundle(NodeBundle {
+                                    style: Style {
+                                        size: bevy::ui::Size::new(
+                                            hud_settings.px(12.0),
+                                            hud_settings.px(12.0),
+                                        ),
+                                        margin: UiRect::all(hud_settings.px(1.0)),
+                                        ..default()
+                                    },
+                                    color: Color::rgb(0.1, 0.1, 0.3).into(),
+                                    ..default()
+                                })
+                                .insert(HeatmapCell { row, col });
+                        }
+                    });
+            }
+        });
+
+    // frame times, one bar per frame against the 60hz step
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: hud_settings.px(50.0),
+                    right: hud_settings.px(10.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(hud_settings.px(4.0)),
+                ..default()
+            },
+            color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            ..default()
+        })
+        .insert(FrameGraphPanel)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: bevy::ui::Size::new(Val::Auto, hud_settings.px(FRAME_GRAPH_HEIGHT)),
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    },
+                    color: Color::NONE.into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for i in 0..FRAME_GRAPH_SAMPLES {
+                        parent
+                            .spawn_bundle(NodeBundle {
+                                style: Style {
+                                    size: bevy::ui::Size::new(
+                                        hud_settings.px(3.0),
+                                        hud_settings.px(0.0),
+                                    ),
+                                    ..default()
+                                },
+                                color: Color::GREEN.into(),
+                                ..default()
+                            })
+                            .insert(FrameGraphBar(i));
+                    }
+                });
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    hud_settings.text(16.0, Color::WHITE),
+                ))
+                .insert(FrameGraphText);
+        });
+
+    // swing power against the power hit threshold in the middle
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: hud_settings.px(40.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: bevy::ui::Size::new(
+                            hud_settings.px(SWING_METER_WIDTH),
+                            hud_settings.px(10.0),
+                        ),
+                        ..default()
+                    },
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: bevy::ui::Size::new(
+                                    hud_settings.px(0.0),
+                                    Val::Percent(100.0),
+                                ),
+                                ..default()
+                            },
+                            color: Color::WHITE.into(),
+                            ..default()
+                        })
+                        .insert(SwingMeterFill);
+                    parent.spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: bevy::ui::Size::new(hud_settings.px(2.0), Val::Percent(100.0)),
+                            position_type: PositionType::Absolute,
+                            position: UiRect {
+                                left: Val::Percent(50.0),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        color: Color::RED.into(),
+                        ..default()
+                    });
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: bevy::ui::Size::new(
+                                    hud_settings.px(2.0),
+                                    Val::Percent(100.0),
+                                ),
+                                position_type: PositionType::Absolute,
+                                ..default()
+                            },
+                            color: Color::YELLOW.into(),
+                            ..default()
+                        })
+                        .insert(SwingMeterPeak);
+                });
+        });
+
+    // combo count and the time left to extend it, hidden without a combo
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                display: Display::None,
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: hud_settings.px(56.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(ComboPanel)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(
+                    TextBundle::from_section("", hud_settings.text(20.0, Color::YELLOW))
+                        .with_style(Style {
+                            margin: UiRect {
+                                right: hud_settings.px(8.0),
+                                ..default()
+                            },
+                            ..default()
+                        }),
+                )
+                .insert(ComboText);
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: bevy::ui::Size::new(
+                            hud_settings.px(COMBO_BAR_WIDTH),
+                            hud_settings.px(6.0),
+                        ),
+                        ..default()
+                    },
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(NodeBundle {
+                            style: Style {
+                                size: bevy::ui::Size::new(
+                                    hud_settings.px(COMBO_BAR_WIDTH),
+                                    Val::Percent(100.0),
+                                ),
+                                ..default()
+                            },
+                            color: Color::YELLOW.into(),
+                            ..default()
+                        })
+                        .insert(ComboBarFill);
+                });
+        });
+
+    // key bindings help, filled in by update_help_text
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", hud_settings.text(20.0, Color::WHITE)).with_style(Style {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(40.0),
+                    left: hud_settings.px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(HelpText);
+
+    // target streak
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", hud_settings.text(24.0, Color::WHITE)).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: hud_settings.px(10.0),
+                    left: hud_settings.px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(TargetSequenceText);
+
+    // pitch count in a fixed length match
+    commands
+        .spawn_bundle(
+            TextBundle::from_section("", hud_settings.text(24.0, Color::WHITE)).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(10.0),
+                    left: hud_settings.px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(MatchText);
+
+    // announcements
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(60.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    "",
+                    hud_settings.text(32.0, Color::WHITE),
+                ))
+                .insert(AnnouncementText);
+        });
+
+    // restart prompt, once the game over screen takes input
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Auto),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Percent(45.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(
+                    TextBundle::from_section("", hud_settings.text(32.0, Color::WHITE))
+                        .with_text_alignment(TextAlignment::CENTER),
+                )
+                .insert(GameOverText);
+        });
+
+    // remaining outs
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: hud_settings.px(10.0),
+                    right: hud_settings.px(10.0),
+                    ..default()
+                },
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            for i in 0..rules.outs_allowed {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: bevy::ui::Size::new(hud_settings.px(20.0), hud_settings.px(20.0)),
+                            margin: UiRect::all(hud_settings.px(4.0)),
+                            ..default()
+                        },
+                        color: Color::WHITE.into(),
+                        ..default()
+                    })
+                    .insert(OutIcon(i));
+            }
+        });
+}
+
+// also runs on the first frame, applying the saved setting
+fn apply_vsync(vsync: Res<Vsync>, mut windows: ResMut<Windows>) {
+    if !vsync.is_changed() || cfg!(target_family = "wasm") {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        window.set_present_mode(if vsync.0 {
+            PresentMode::Fifo
+        } else {
+            PresentMode::Immediate
+        });
+    }
+}
+
+// below full scale the 3d camera draws into an image sized to match and a 2d camera stretches
+// it over the window, rebuilt when the window resizes
+// the cursor is still read in window coordinates, so aiming doesn't change
+fn apply_render_scale(
+    mut commands: Commands,
+    render_scale: Res<RenderScale>,
+    mut resized: EventReader<WindowResized>,
+    windows: Res<Windows>,
+    mut images: ResMut<Assets<Image>>,
+    mut q_camera: Query<&mut Camera, With<Camera3d>>,
+    q_blit: Query<Entity, With<RenderScaleBlit>>,
+) {
+    let resized = resized.iter().any(|event| event.id == WindowId::primary());
+    if !render_scale.is_changed() && !resized {
+        return;
+    }
+
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let mut camera = match q_camera.get_single_mut() {
+        Ok(camera) => camera,
+        Err(_) => return,
+    };
+
+    // the old image goes with its sprite
+    for blit in q_blit.iter() {
+        commands.entity(blit).despawn_recursive();
+    }
+
+    if render_scale.0 >= 1.0 {
+        camera.target = RenderTarget::Window(WindowId::primary());
+        return;
+    }
+
+    let size = Extent3d {
+        width: ((window.physical_width() as f32 * render_scale.0) as u32).max(1),
+        height: ((window.physical_height() as f32 * render_scale.0) as u32).max(1),
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+    camera.target = RenderTarget::Image(image.clone());
+
+    commands
+        .spawn_bundle(Camera2dBundle {
+            camera: Camera {
+                priority: 1,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(RenderScaleBlit);
+    commands
+        .spawn_bundle(SpriteBundle {
+            texture: image,
+            sprite: Sprite {
+                custom_size: Some(vec2(window.width(), window.height())),
+                ..default()
+            },
+            ..default()
+        })
+        .insert(RenderScaleBlit);
+}
+
+// the hud goes on whichever camera draws to the window, and nowhere in photo mode
+fn show_ui_on_window_camera(
+    mut commands: Commands,
+    app_state: Res<State<AppState>>,
+    q_cameras: Query<(Entity, &Camera, Option<&UiCameraConfig>)>,
+) {
+    let photo_mode = *app_state.current() == AppState::PhotoMode
+        || app_state.inactives().contains(&AppState::PhotoMode);
+
+    for (entity, camera, ui_camera_config) in q_cameras.iter() {
+        let show_ui = !photo_mode && matches!(camera.target, RenderTarget::Window(_));
+        if ui_camera_config.map_or(true, |config| config.show_ui) != show_ui {
+            commands.entity(entity).insert(UiCameraConfig { show_ui });
+        }
+    }
+}
+
+// sleeps out the rest of the frame, bat velocity is sampled on a fixed 60hz step so hit
+// power doesn't depend on the cap
+fn limit_frame_rate(frame_cap: Res<FrameCap>, mut frame_start: Local<Option<Instant>>) {
+    if cfg!(target_family = "wasm") {
+        return;
+    }
+
+    if let (Some(cap), Some(start)) = (frame_cap.0, *frame_start) {
+        let frame_time = Duration::from_secs_f64(1.0 / cap);
+        let elapsed = start.elapsed();
+        if elapsed < frame_time {
+            std::thread::sleep(frame_time - elapsed);
+        }
+    }
+    *frame_start = Some(Instant::now());
+}
+
+fn toggle_frame_graph(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut show_frame_graph: ResMut<ShowFrameGraph>,
+    mut q: Query<&mut Style, With<FrameGraphPanel>>,
+) {
+    if !keys.just_pressed(key_bindings.toggle_frame_graph) {
+        return;
+    }
+
+    show_frame_graph.0 = !show_frame_graph.0;
+    for mut style in q.iter_mut() {
+        style.display = if show_frame_graph.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+// bars over the 60hz step mean frames are slower than the historic velocity loop
+fn update_frame_graph(
+    time: Res<Time>,
+    vsync: Res<Vsync>,
+    frame_cap: Res<FrameCap>,
+    show_frame_graph: Res<ShowFrameGraph>,
+    hud_settings: Res<HudSettings>,
+    mut frame_times: ResMut<FrameTimes>,
+    mut q_bars: Query<(&FrameGraphBar, &mut Style, &mut UiColor)>,
+    mut q_text: Query<&mut Text, With<FrameGraphText>>,
+) {
+    frame_times.0.push_back(time.delta_seconds());
+    if frame_times.0.len() > FRAME_GRAPH_SAMPLES {
+        frame_times.0.pop_front();
+    }
+
+    if !show_frame_graph.0 {
+        return;
+    }
+
+    for (bar, mut style, mut color) in q_bars.iter_mut() {
+        let frame_time = frame_times.0.get(bar.0).copied().unwrap_or(0.0);
+        style.size.height =
+            hud_settings.px((frame_time * 1000.0 * FRAME_GRAPH_SCALE).min(FRAME_GRAPH_HEIGHT));
+        *color = if frame_time <= 1.0 / 60.0 + 0.001 {
+            Color::GREEN.into()
+        } else {
+            Color::RED.into()
+        };
+    }
+
+    let average = frame_times.0.iter().sum::<f32>() / frame_times.0.len().max(1) as f32;
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = format!(
+            "{:.1} ms, {:.0} fps, vsync {}, cap {}",
+            average * 1000.0,
+            1.0 / average.max(0.001),
+            if vsync.0 { "on" } else { "off" },
+            frame_cap
+                .0
+                .map_or("off".to_string(), |cap| format!("{:.0}", cap))
+        );
+    }
+}
+
+// same swing power the bat hits with, the fill turns green past the threshold
+fn update_swing_meter(
+    time: Res<Time>,
+    physics_config: Res<PhysicsConfig>,
+    bat_weight: Res<BatWeight>,
+    hud_settings: Res<HudSettings>,
+    mut swing_peak: ResMut<SwingPeak>,
+    q_colliders: Query<&HistoricVelocity, With<BatCollider>>,
+    mut q_fill: Query<(&mut Style, &mut UiColor), With<SwingMeterFill>>,
+    mut q_peak: Query<&mut Style, (With<SwingMeterPeak>, Without<SwingMeterFill>)>,
+) {
+    let threshold = physics_config.power_hit_threshold.max(0.01);
+    let power = q_colliders
+        .iter()
+        .map(|historic_velocity| historic_velocity.decaying_vel.length() * bat_weight.power())
+        .fold(0.0, f32::max);
+    swing_peak.0 = (swing_peak.0 - SWING_PEAK_DECAY * time.delta_seconds()).max(power);
+
+    let width =
+        |power: f32| hud_settings.px((power / (2.0 * threshold)).min(1.0) * SWING_METER_WIDTH);
+    for (mut style, mut color) in q_fill.iter_mut() {
+        style.size.width = width(power);
+        *color = if power > threshold {
+            Color::GREEN.into()
+        } else if power > threshold * NEAR_POWER_HIT {
+            Color::YELLOW.into()
+        } else {
+            Color::WHITE.into()
+        };
+    }
+    for mut style in q_peak.iter_mut() {
+        style.position.left = width(swing_peak.0);
+    }
+}
+
+fn update_combo_bar(
+    combo: Res<Combo>,
+    hud_settings: Res<HudSettings>,
+    mut q_panel: Query<&mut Style, With<ComboPanel>>,
+    mut q_fill: Query<&mut Style, (With<ComboBarFill>, Without<ComboPanel>)>,
+    mut q_text: Query<&mut Text, With<ComboText>>,
+) {
+    let display = if combo.count > 0 {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    for mut style in q_panel.iter_mut() {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+    if combo.count == 0 {
+        return;
+    }
+
+    for mut style in q_fill.iter_mut() {
+        style.size.width =
+            hud_settings.px((combo.timer / COMBO_WINDOW).clamp(0.0, 1.0) * COMBO_BAR_WIDTH);
+    }
+    if combo.is_changed() {
+        for mut text in q_text.iter_mut() {
+            text.sections[0].value = format!("Combo x{}", combo.count);
+        }
+    }
+}
+
+fn toggle_heatmap(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut show_heatmap: ResMut<ShowHeatmap>,
+    mut hit_heatmap: ResMut<HitHeatmap>,
+    mut q: Query<&mut Style, With<HeatmapPanel>>,
+) {
+    if keys.just_pressed(key_bindings.reset_heatmap) {
+        *hit_heatmap = HitHeatmap::default();
+    }
+
+    if !keys.just_pressed(key_bindings.toggle_heatmap) {
+        return;
+    }
+
+    show_heatmap.0 = !show_heatmap.0;
+    for mut style in q.iter_mut() {
+        style.display = if show_heatmap.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+fn update_heatmap_display(
+    hit_heatmap: Res<HitHeatmap>,
+    mut q: Query<(&HeatmapCell, &mut UiColor)>,
+) {
+    if !hit_heatmap.is_changed() {
+        return;
+    }
+
+    // relative to the busiest cell, cold blue to hot red
+    let max = hit_heatmap
+        .counts
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    for (cell, mut color) in q.iter_mut() {
+        let heat = hit_heatmap.counts[cell.row][cell.col] as f32 / max as f32;
+        color.0 = Color::rgb(
+            0.1 + 0.9 * heat,
+            0.1 + 0.6 * heat * (1.0 - heat),
+            0.3 * (1.0 - heat),
+        );
+    }
+}
+
+fn update_out_icons(outs: Res<Outs>, mut q: Query<(&OutIcon, &mut UiColor)>) {
+    if !outs.is_changed() {
+        return;
+    }
+
+    for (icon, mut color) in q.iter_mut() {
+        color.0 = if icon.0 < outs.0 {
+            Color::RED
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+fn show_inning_summary(
+    inning: Res<Inning>,
+    inning_scores: Res<InningScores>,
+    outs: Res<Outs>,
+    mut q_panel: Query<&mut Style, With<InningSummaryPanel>>,
+    mut q_text: Query<&mut Text, With<InningSummaryText>>,
+) {
+    let innings = inning_scores
+        .0
+        .iter()
+        .enumerate()
+        .map(|(i, runs)| format!("{}: {}", i + 1, runs))
+        .collect::<Vec<_>>()
+        .join("   ");
+
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = format!(
+            "End of inning {}\nscored {} with {} outs\n\n{}",
+            inning.number,
+            inning_scores.0.last().copied().unwrap_or(0),
+            outs.0,
+            innings
+        );
+    }
+    for mut style in q_panel.iter_mut() {
+        style.display = Display::Flex;
+    }
+}
+
+fn hide_inning_summary(mut q: Query<&mut Style, With<InningSummaryPanel>>) {
+    for mut style in q.iter_mut() {
+        style.display = Display::None;
+    }
+}
+
+fn update_share_code_text(
+    share_code_input: Res<ShareCodeInput>,
+    mut q: Query<&mut Text, With<ShareCodeText>>,
+) {
+    if !share_code_input.is_changed() {
+        return;
+    }
+
+    for mut text in q.iter_mut() {
+        text.sections[0].value = if share_code_input.active {
+            format!(
+                "share code: {}_\nenter to watch, escape to cancel",
+                share_code_input.text
+            )
+        } else {
+            String::new()
+        };
+    }
+}
+
+fn update_match_text(
+    match_length: Res<MatchLength>,
+    pitch_count: Res<PitchCount>,
+    mut q: Query<&mut Text, With<MatchText>>,
+) {
+    if !match_length.is_changed() && !pitch_count.is_changed() {
+        return;
+    }
+
+    let value = match match_length.0 {
+        Some(limit) => format!("Pitch {}/{}", pitch_count.0.min(limit), limit),
+        None => String::new(),
+    };
+    for mut text in q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn update_wind_text(
+    wind: Res<Wind>,
+    q_camera: Query<&GlobalTransform, With<Camera3d>>,
+    mut q: Query<&mut Text, With<WindText>>,
+) {
+    if !wind.is_changed() {
+        return;
+    }
+
+    let value = match q_camera.get_single() {
+        Ok(camera) if wind.0.length() > 0.05 => {
+            // screen up is camera forward, clockwise from there
+            let forward = camera.forward();
+            let right = camera.right();
+            let angle = wind.0.dot(right).atan2(wind.0.dot(forward));
+            let arrows = ['↑', '↗', '→', '↘', '↓', '↙', '←', '↖'];
+            let index = (angle / std::f32::consts::FRAC_PI_4).round() as i32;
+            format!(
+                "wind {} {:.1}",
+                arrows[index.rem_euclid(8) as usize],
+                wind.0.length()
+            )
+        }
+        _ => "wind calm".to_string(),
+    };
+    for mut text in q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn update_announcement(
+    time: Res<Time>,
+    mut announcement: ResMut<Announcement>,
+    mut q: Query<&mut Text, With<AnnouncementText>>,
+) {
+    if announcement.timer <= 0.0 {
+        return;
+    }
+
+    announcement.timer -= time.delta_seconds();
+
+    let value = if announcement.timer > 0.0 {
+        announcement.text.clone()
+    } else {
+        String::new()
+    };
+    for mut text in q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn update_game_over_text(
+    app_state: Res<State<AppState>>,
+    key_bindings: Res<KeyBindings>,
+    game_over_timer: Res<GameOverTimer>,
+    confirm_quit: Res<ConfirmQuit>,
+    stats: Res<Stats>,
+    (match_length, pitch_count): (Res<MatchLength>, Res<PitchCount>),
+    run_history: Res<RunHistory>,
+    mut q: Query<&mut Text, With<GameOverText>>,
+) {
+    let value = if *app_state.current() != AppState::GameOver || game_over_timer.0 > 0.0 {
+        String::new()
+    } else if confirm_quit.0 {
+        "Press Escape again to quit".to_string()
+    } else if cfg!(target_family = "wasm") {
+        format!("Press {:?} to restart", key_bindings.restart)
+    } else {
+        format!(
+            "Press {:?} to restart\nEscape to quit",
+            key_bindings.restart
+        )
+    };
+    let value = if stats.sandbox && !value.is_empty() {
+        format!("Sandbox run, not counted\n{}", value)
+    } else {
+        value
+    };
+    let value = match match_length.0 {
+        Some(limit) if match_length.finished(&pitch_count) && !value.is_empty() => format!(
+            "{} pitch match complete\n{} hits, {} power hits\n{}",
+            limit, stats.hits, stats.power_hits, value
+        ),
+        _ => value,
+    };
+    let value = if run_history.0.is_empty() || value.is_empty() {
+        value
+    } else {
+        let now = unix_time();
+        let runs = run_history
+            .0
+            .iter()
+            .rev()
+            .map(|run| {
+                format!(
+                    "{}  seed {}  {}",
+                    run.score,
+                    run.seed,
+                    format_age(now.saturating_sub(run.timestamp))
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("{}\n\nRecent runs\n{}", value, runs.join("\n"))
+    };
+    for mut text in q.iter_mut() {
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+// roughly, the exact time isn't worth the room
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+// everything was built at the old scale, so scale it all by the change
+fn rescale_hud(
+    hud_settings: Res<HudSettings>,
+    mut built_scale: Local<Option<f32>>,
+    mut q_text: Query<&mut Text, With<Node>>,
+    mut q_style: Query<&mut Style>,
+) {
+    if !hud_settings.is_changed() {
+        return;
+    }
+    let ratio = match *built_scale {
+        Some(scale) if scale != hud_settings.scale => hud_settings.scale / scale,
+        _ => {
+            *built_scale = Some(hud_settings.scale);
+            return;
+        }
+    };
+    *built_scale = Some(hud_settings.scale);
+
+    for mut text in q_text.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.font_size *= ratio;
+        }
+    }
+
+    let scale = |val: &mut Val| {
+        if let Val::Px(px) = val {
+            *px *= ratio;
+        }
+    };
+    let scale_rect = |rect: &mut UiRect<Val>| {
+        scale(&mut rect.left);
+        scale(&mut rect.right);
+        scale(&mut rect.top);
+        scale(&mut rect.bottom);
+    };
+    for mut style in q_style.iter_mut() {
+        scale(&mut style.size.width);
+        scale(&mut style.size.height);
+        scale_rect(&mut style.position);
+        scale_rect(&mut style.margin);
+        scale_rect(&mut style.padding);
+    }
+}
+
+fn spawn_settings_menu(mut commands: Commands, hud_settings: Res<HudSettings>) {
+    let text_style = |color| hud_settings.text(24.0, color);
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: bevy::ui::Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(SettingsPanel)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        // ui y runs up, reversed so the first row is on top
+                        flex_direction: FlexDirection::ColumnReverse,
+                        padding: UiRect::all(hud_settings.px(16.0)),
+                        ..default()
+                    },
+                    color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle::from_section(
+                        "Settings",
+                        text_style(Color::WHITE),
+                    ));
+                    for i in 0..SettingsItem::ALL.len() {
+                        parent
+                            .spawn_bundle(TextBundle::from_section("", text_style(Color::GRAY)))
+                            .insert(SettingsRow(i));
+                    }
+                    parent.spawn_bundle(TextBundle::from_section(
+                        "Up and down to choose, left and right to change, Escape to close",
+                        hud_settings.text(16.0, Color::GRAY),
+                    ));
+                });
+        });
+}
+
+fn update_settings_menu(
+    settings_menu: Res<SettingsMenu>,
+    settings: Settings,
+    mut q: Query<(&SettingsRow, &mut Text)>,
+) {
+    for (row, mut text) in q.iter_mut() {
+        let item = SettingsItem::ALL[row.0];
+        text.sections[0].value = format!("{}: {}", item.label(), settings.value(item));
+        text.sections[0].style.color = if row.0 == settings_menu.selected {
+            Color::YELLOW
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
+// everything is saved on the way out rather than on every press
+fn close_settings_menu(
+    mut commands: Commands,
+    settings: Settings,
+    q: Query<Entity, With<SettingsPanel>>,
+) {
+    for entity in q.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    save_config("ld51_audio", &*settings.audio);
+    save_config("ld51_shake_intensity", &*settings.shake_intensity);
+    save_config("ld51_reduce_motion", &*settings.reduce_motion);
+    save_config("ld51_swing_glow", &*settings.swing_glow);
+    save_config("ld51_hot_balls", &*settings.hot_balls);
+    save_config("ld51_ground_grid", &*settings.ground_grid);
+    save_config("ld51_vsync", &*settings.vsync);
+    save_config("ld51_frame_cap", &*settings.frame_cap);
+    save_config("ld51_render_scale", &*settings.render_scale);
+    save_config("ld51_cursor_mode", &*settings.cursor_mode);
+    save_config("ld51_mouse_aim", &*settings.mouse_aim);
+    save_config("ld51_hud", &*settings.hud_settings);
+    save_config("ld51_camera_preset", &*settings.camera_preset);
+    save_config("ld51_pitch_difficulty", &*settings.show_pitch_difficulty);
+    save_config("ld51_onboarding", &*settings.onboarding);
+    save_config("ld51_match_length", &*settings.match_length);
+    save_config("ld51_restart_delay", &*settings.restart_delay);
+}
+
+fn update_target_sequence_display(
+    target_sequence: Res<TargetSequence>,
+    target_assets: Res<TargetAssets>,
+    mut q_targets: Query<(&Target, &mut Handle<StandardMaterial>)>,
+    mut q_text: Query<&mut Text, With<TargetSequenceText>>,
+) {
+    if !target_sequence.is_changed() {
+        return;
+    }
+
+    // highlight the next target
+    for (target, mut material) in q_targets.iter_mut() {
+        *material = if target.0 == target_sequence.index {
+            target_assets.active.clone()
+        } else {
+            target_assets.idle.clone()
+        };
+    }
+
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = if target_sequence.enabled {
+            format!(
+                "streak {}  time {:.1}",
+                target_sequence.streak, target_sequence.timer
+            )
+        } else {
+            String::new()
+        };
+    }
+}
+
+fn toggle_help(
+    keys: Res<Input<KeyCode>>,
+    key_bindings: Res<KeyBindings>,
+    mut q: Query<&mut Style, With<HelpText>>,
+) {
+    if !keys.just_pressed(key_bindings.help) {
+        return;
+    }
+
+    for mut style in q.iter_mut() {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            Display::Flex => Display::None,
+        };
+    }
+}
+
+fn update_help_text(
+    key_bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    mut q: Query<(&mut Text, &mut Style), With<HelpText>>,
+) {
+    if !key_bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    let help = key_bindings
+        .entries()
+        .iter()
+        .enumerate()
+        .map(|(i, (action, key))| {
+            if rebinding.0 == Some(i) {
+                format!("> press a key for {} (Escape keeps {:?})", action, key)
+            } else {
+                format!("{:?}  {}", key, action)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    for (mut text, mut style) in q.iter_mut() {
+        text.sections[0].value = help.clone();
+
+        if rebinding.0.is_some() {
+            style.display = Display::Flex;
+        }
+    }
+}
+
+// only flips segment visibility, the geometry is built once in setup
+fn update_scoreboard(score: Res<Score>, mut q: Query<(&ScoreSegment, &mut Visibility)>) {
+    if !score.is_changed() {
+        return;
+    }
+
+    for (score_segment, mut visibility) in q.iter_mut() {
+        let place = 10u32.pow(score_segment.digit as u32);
+
+        // blank leading zeros, except for the ones digit
+        visibility.is_visible = if score.0 < place && score_segment.digit > 0 {
+            false
+        } else {
+            let digit = (score.0 / place % 10) as usize;
+            SEVEN_SEGMENT_DIGITS[digit] & (1 << score_segment.segment) != 0
+        };
+    }
+}
+
+fn update_radar_text(
+    time: Res<Time>,
+    last_hit: Res<LastHit>,
+    session_bests: Res<SessionBests>,
+    mut last_pitch: ResMut<LastPitch>,
+    mut q: Query<&mut Text, With<RadarText>>,
+) {
+    if last_pitch.timer <= 0.0 {
+        return;
+    }
+
+    last_pitch.timer -= time.delta_seconds();
+
+    let value = if last_pitch.timer <= 0.0 {
+        String::new()
+    } else {
+        match last_pitch.exit_speed {
+            Some(exit_speed) => format!(
+                "pitch {:.0} mph  exit {:.0} mph at {:.0}°\nbest {:.0} mph at {:.0}°",
+                last_pitch.speed * RADAR_MPH,
+                exit_speed * RADAR_MPH,
+                last_hit.launch_angle,
+                session_bests.exit_speed * RADAR_MPH,
+                session_bests.launch_angle,
+            ),
+            None => format!("pitch {:.0} mph", last_pitch.speed * RADAR_MPH),
+        }
+    };
+    for mut text in q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}